@@ -0,0 +1,60 @@
+//! Compares the sequential and parallel iteration over a 1M-entity list.
+//!
+//! Run with `cargo bench --features rayon --bench par_iter`.
+
+use entity_list::EntityList;
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+
+const ENTITIES: u64 = 1_000_000;
+const ITERATIONS: u32 = 20;
+
+fn work(v: u64) -> u64 {
+    (0..64).fold(v, |acc, i| {
+        acc.wrapping_mul(6364136223846793005).wrapping_add(i)
+    })
+}
+
+fn measure(name: &str, mut f: impl FnMut() -> u64) -> Duration {
+    let start = Instant::now();
+    let mut checksum = 0u64;
+    for _ in 0..ITERATIONS {
+        checksum = checksum.wrapping_add(f());
+    }
+    let elapsed = start.elapsed() / ITERATIONS;
+    println!(
+        "{}: {:?} per iteration (checksum {})",
+        name, elapsed, checksum
+    );
+    elapsed
+}
+
+fn main() {
+    let (mut list, ids) = EntityList::from_iter_with_ids(0..ENTITIES);
+    // Make some gaps so that dead slots are skipped as in practice.
+    for id in ids.iter().step_by(10) {
+        list.remove(*id);
+    }
+
+    let seq = measure("iter", || {
+        list.iter()
+            .map(|(_, v)| work(*v))
+            .fold(0, u64::wrapping_add)
+    });
+    let par = measure("par_iter", || {
+        list.par_iter()
+            .map(|(_, v)| work(*v))
+            .reduce(|| 0, u64::wrapping_add)
+    });
+    println!("speedup: {:.2}x", seq.as_secs_f64() / par.as_secs_f64());
+
+    let seq = measure("iter_mut", || {
+        list.iter_mut().for_each(|(_, v)| *v = work(*v));
+        0
+    });
+    let par = measure("par_iter_mut", || {
+        list.par_iter_mut().for_each(|(_, v)| *v = work(*v));
+        0
+    });
+    println!("speedup: {:.2}x", seq.as_secs_f64() / par.as_secs_f64());
+}
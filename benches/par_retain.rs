@@ -0,0 +1,59 @@
+//! Compares the sequential and parallel retain over a 1M-entity list with a CPU-bound
+//! predicate.
+//!
+//! Run with `cargo bench --features rayon --bench par_retain`.
+
+use entity_list::EntityList;
+use std::time::{Duration, Instant};
+
+const ENTITIES: u64 = 1_000_000;
+const ITERATIONS: u32 = 10;
+
+fn work(v: u64) -> u64 {
+    (0..256).fold(v, |acc, i| {
+        acc.wrapping_mul(6364136223846793005).wrapping_add(i)
+    })
+}
+
+fn keep(v: u64) -> bool {
+    work(v) % 3 < 2
+}
+
+/// Measures only `f`, since cloning the list for each iteration would dominate otherwise.
+fn measure(
+    name: &str,
+    list: &EntityList<u64>,
+    mut f: impl FnMut(&mut EntityList<u64>),
+) -> Duration {
+    let mut elapsed = Duration::default();
+    let mut remaining = 0;
+    for _ in 0..ITERATIONS {
+        let mut list = list.clone();
+        let start = Instant::now();
+        f(&mut list);
+        elapsed += start.elapsed();
+        remaining = list.len();
+    }
+    let elapsed = elapsed / ITERATIONS;
+    println!(
+        "{}: {:?} per iteration ({} remaining)",
+        name, elapsed, remaining
+    );
+    elapsed
+}
+
+fn main() {
+    let (mut list, ids) = EntityList::from_iter_with_ids(0..ENTITIES);
+    // Make some gaps so that dead slots are skipped as in practice.
+    for id in ids.iter().step_by(10) {
+        list.remove(*id);
+    }
+
+    let seq = measure("retain_collect", &list, |list| {
+        list.retain_collect(|_, v| if keep(*v) { Some(()) } else { None });
+    });
+    let par = measure("par_retain", &list, |list| {
+        list.par_retain(|_, v| keep(*v));
+    });
+    println!("speedup: {:.2}x", seq.as_secs_f64() / par.as_secs_f64());
+}
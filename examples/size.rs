@@ -1,5 +1,5 @@
 use entity_list::EntityDynIter;
 
 fn main() {
-    println!("{}", std::mem::size_of::<EntityDynIter>())
-}
\ No newline at end of file
+    println!("{}", std::mem::size_of::<EntityDynIter<u32>>())
+}
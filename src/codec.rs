@@ -0,0 +1,138 @@
+//! A flat binary format for EntityLists of plain-old-data entities.
+//!
+//! The layout is a 16 byte header followed by one record per slot, including dead ones so that
+//! the generations are preserved.
+//!
+//! | Field      | Size                   |                                     |
+//! |------------|------------------------|-------------------------------------|
+//! | magic      | 4                      | `b"ENTL"`                           |
+//! | version    | 1                      | [`VERSION`]                         |
+//! | padding    | 3                      |                                     |
+//! | slot count | 4                      | u32 little endian                   |
+//! | alive      | 4                      | u32 little endian                   |
+//! | records    | (8 + size of T) * slot | `(gen: u32, alive: u8, pad: [u8; 3], entity: T)` |
+//!
+//! The generation is little endian, while the entity is written in the native byte order as is.
+//! A dead slot has zeroed entity bytes.
+
+use crate::{EntityEntry, EntityList, EntityListImpl};
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+const MAGIC: &[u8; 4] = b"ENTL";
+pub const VERSION: u8 = 1;
+/// The most bytes of slots allocated ahead of reading them, since the slot count in the header
+/// may be bogus.
+const MAX_PREALLOC_BYTES: usize = 1 << 20;
+
+pub fn encode<T: bytemuck::Pod>(list: &EntityList<T>, w: &mut impl Write) -> Result<()> {
+    w.write_all(MAGIC)?;
+    w.write_all(&[VERSION, 0, 0, 0])?;
+    w.write_all(&(list.0.len() as u32).to_le_bytes())?;
+    w.write_all(&(list.len() as u32).to_le_bytes())?;
+    let zeroed = T::zeroed();
+    for entry in &list.0 {
+        w.write_all(&entry.gen.to_le_bytes())?;
+        w.write_all(&[entry.entity.is_some() as u8, 0, 0, 0])?;
+        w.write_all(bytemuck::bytes_of(entry.entity.as_ref().unwrap_or(&zeroed)))?;
+    }
+    Ok(())
+}
+
+pub fn decode<T: bytemuck::Pod>(r: &mut impl Read) -> Result<EntityList<T>> {
+    let mut header = [0u8; 16];
+    r.read_exact(&mut header)?;
+    if &header[..4] != MAGIC {
+        return Err(Error::new(ErrorKind::InvalidData, "not an EntityList"));
+    }
+    if header[4] != VERSION {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("unsupported EntityList version {}", header[4]),
+        ));
+    }
+    let read_u32 = |bytes: &[u8]| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let slot_count = read_u32(&header[8..12]) as usize;
+    let alive = read_u32(&header[12..16]) as usize;
+
+    let record_size = std::mem::size_of::<EntityEntry<T>>();
+    let mut slots = Vec::with_capacity(slot_count.min(MAX_PREALLOC_BYTES / record_size));
+    let mut record = vec![0u8; 8 + std::mem::size_of::<T>()];
+    for _ in 0..slot_count {
+        r.read_exact(&mut record)?;
+        let gen = read_u32(&record[..4]);
+        let entity = match record[4] {
+            0 => None,
+            1 => Some(bytemuck::pod_read_unaligned(&record[8..])),
+            _ => return Err(Error::new(ErrorKind::InvalidData, "invalid alive flag")),
+        };
+        slots.push(EntityEntry { gen, entity });
+    }
+
+    let mut list = EntityListImpl(slots, Default::default());
+    list.rebuild_free_list();
+    if list.len() != alive {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "alive count does not match the records",
+        ));
+    }
+    list.validate()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode};
+    use crate::EntityList;
+
+    #[test]
+    fn round_trip() {
+        let mut el = EntityList::default();
+        let a = el.add([1u32, 2]);
+        let b = el.add([3u32, 4]);
+        el.remove(a);
+        let c = el.add([5u32, 6]);
+        let d = el.add([7u32, 8]);
+        el.remove(d);
+
+        let mut buf = vec![];
+        encode(&el, &mut buf).unwrap();
+        assert_eq!(buf.len(), 16 + 3 * (8 + 8));
+
+        let de: EntityList<[u32; 2]> = decode(&mut &buf[..]).unwrap();
+        assert_eq!(de.slot_count(), 3);
+        assert_eq!(de.get(a), None);
+        assert_eq!(de.get(b), Some(&[3, 4]));
+        assert_eq!(de.get(c), Some(&[5, 6]));
+        assert_eq!(de.get(d), None);
+
+        buf[0] = b'X';
+        assert!(decode::<[u32; 2]>(&mut &buf[..]).is_err());
+    }
+
+    #[test]
+    fn oversized_header() {
+        let mut el = EntityList::default();
+        el.add([1u32, 2]);
+        let mut buf = vec![];
+        encode(&el, &mut buf).unwrap();
+
+        buf[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(decode::<[u32; 2]>(&mut &buf[..]).is_err());
+        assert!(decode::<[u32; 2]>(&mut &buf[..16]).is_err());
+        assert!(decode::<[u32; 2]>(&mut &buf[..10]).is_err());
+    }
+
+    #[test]
+    fn exhausted_generation() {
+        let mut el = EntityList::default();
+        el.add([1u32, 2]);
+        let mut buf = vec![];
+        encode(&el, &mut buf).unwrap();
+
+        buf[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+        let err = decode::<[u32; 2]>(&mut &buf[..]).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
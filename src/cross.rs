@@ -0,0 +1,41 @@
+use crate::{EntityId, EntityList};
+
+/// Borrows an entity from each of two different lists mutably at the same time, e.g. to update
+/// both sides of a collision between entities of different kinds.
+///
+/// Each side is None if its EntityId is stale, independently of the other.
+pub fn cross_borrow_mut<'a, T, U>(
+    a: &'a mut EntityList<T>,
+    a_id: EntityId,
+    b: &'a mut EntityList<U>,
+    b_id: EntityId,
+) -> (Option<&'a mut T>, Option<&'a mut U>) {
+    (a.get_mut(a_id), b.get_mut(b_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cross_borrow_mut;
+    use crate::{Entity, EntityList};
+
+    #[test]
+    fn cross_borrow_mut_two_lists() {
+        let mut entities = EntityList::default();
+        let mut counts = EntityList::default();
+        let a = entities.add(Entity { name: "a" });
+        let b = counts.add(1);
+        let stale = counts.add(2);
+        counts.remove(stale);
+
+        let (entity, count) = cross_borrow_mut(&mut entities, a, &mut counts, b);
+        let (entity, count) = (entity.unwrap(), count.unwrap());
+        entity.name = "aa";
+        *count += 1;
+        assert_eq!(entities[a].name, "aa");
+        assert_eq!(counts[b], 2);
+
+        let (entity, count) = cross_borrow_mut(&mut entities, a, &mut counts, stale);
+        assert!(entity.is_some());
+        assert!(count.is_none());
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{EntityEntry, EntityId, EntityList};
+
+fn entry_at<T>(slots: &[EntityEntry<T>], idx: usize) -> Option<(EntityId, &T)> {
+    let entry = slots.get(idx)?;
+    Some((
+        EntityId {
+            id: idx as u32,
+            gen: entry.gen,
+        },
+        entry.entity.as_ref()?,
+    ))
+}
+
+fn next_alive<T>(slots: &[EntityEntry<T>], idx: usize) -> Option<usize> {
+    (idx + 1..slots.len()).find(|&i| slots[i].entity.is_some())
+}
+
+fn prev_alive<T>(slots: &[EntityEntry<T>], idx: usize) -> Option<usize> {
+    (0..idx.min(slots.len()))
+        .rev()
+        .find(|&i| slots[i].entity.is_some())
+}
+
+/// A cursor pointing to an alive entity, which can move to the next or previous alive entities
+/// in slot order.
+pub struct EntityCursor<'a, T> {
+    list: &'a EntityList<T>,
+    idx: usize,
+}
+
+impl<'a, T> EntityCursor<'a, T> {
+    pub fn current(&self) -> Option<(EntityId, &'a T)> {
+        entry_at(&self.list.0, self.idx)
+    }
+
+    /// Moves to the next alive entity. Returns false and stays if there is none.
+    pub fn advance(&mut self) -> bool {
+        if let Some(idx) = next_alive(&self.list.0, self.idx) {
+            self.idx = idx;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Moves to the previous alive entity. Returns false and stays if there is none.
+    pub fn retreat(&mut self) -> bool {
+        if let Some(idx) = prev_alive(&self.list.0, self.idx) {
+            self.idx = idx;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn peek_next(&self) -> Option<(EntityId, &'a T)> {
+        entry_at(&self.list.0, next_alive(&self.list.0, self.idx)?)
+    }
+
+    pub fn peek_prev(&self) -> Option<(EntityId, &'a T)> {
+        entry_at(&self.list.0, prev_alive(&self.list.0, self.idx)?)
+    }
+}
+
+/// Mutable version of [`EntityCursor`].
+pub struct EntityCursorMut<'a, T> {
+    list: &'a mut EntityList<T>,
+    idx: usize,
+}
+
+impl<'a, T> EntityCursorMut<'a, T> {
+    pub fn current(&self) -> Option<(EntityId, &T)> {
+        entry_at(&self.list.0, self.idx)
+    }
+
+    pub fn current_mut(&mut self) -> Option<(EntityId, &mut T)> {
+        let entry = self.list.0.get_mut(self.idx)?;
+        Some((
+            EntityId {
+                id: self.idx as u32,
+                gen: entry.gen,
+            },
+            entry.entity.as_mut()?,
+        ))
+    }
+
+    pub fn advance(&mut self) -> bool {
+        if let Some(idx) = next_alive(&self.list.0, self.idx) {
+            self.idx = idx;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn retreat(&mut self) -> bool {
+        if let Some(idx) = prev_alive(&self.list.0, self.idx) {
+            self.idx = idx;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn peek_next(&self) -> Option<(EntityId, &T)> {
+        entry_at(&self.list.0, next_alive(&self.list.0, self.idx)?)
+    }
+
+    pub fn peek_prev(&self) -> Option<(EntityId, &T)> {
+        entry_at(&self.list.0, prev_alive(&self.list.0, self.idx)?)
+    }
+}
+
+impl<T> EntityList<T> {
+    /// Returns a cursor pointing to `id`, or None if it is stale.
+    pub fn cursor(&self, id: EntityId) -> Option<EntityCursor<'_, T>> {
+        self.get(id)?;
+        Some(EntityCursor {
+            list: self,
+            idx: id.id as usize,
+        })
+    }
+
+    pub fn cursor_mut(&mut self, id: EntityId) -> Option<EntityCursorMut<'_, T>> {
+        self.get(id)?;
+        Some(EntityCursorMut {
+            list: self,
+            idx: id.id as usize,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Entity, EntityList};
+
+    #[test]
+    fn cursor() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        let d = el.add(Entity { name: "d" });
+        el.remove(b);
+        assert!(el.cursor(b).is_none());
+
+        let mut cursor = el.cursor(c).unwrap();
+        assert_eq!(cursor.current().map(|(id, e)| (id, e.name)), Some((c, "c")));
+        assert_eq!(cursor.peek_prev().map(|(id, _)| id), Some(a));
+        assert_eq!(cursor.peek_next().map(|(id, _)| id), Some(d));
+
+        assert!(cursor.advance());
+        assert_eq!(cursor.current().map(|(id, _)| id), Some(d));
+        assert!(!cursor.advance());
+        assert_eq!(cursor.peek_next().map(|(id, _)| id), None);
+
+        assert!(cursor.retreat());
+        assert!(cursor.retreat());
+        assert_eq!(cursor.current().map(|(id, _)| id), Some(a));
+        assert!(!cursor.retreat());
+        assert_eq!(cursor.peek_prev().map(|(id, _)| id), None);
+    }
+
+    #[test]
+    fn cursor_mut() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+
+        let mut cursor = el.cursor_mut(a).unwrap();
+        while let Some((_, e)) = cursor.current_mut() {
+            e.name = "x";
+            if !cursor.advance() {
+                break;
+            }
+        }
+        assert_eq!(cursor.current().map(|(id, _)| id), Some(b));
+        assert_eq!(el.get_many([a, b]), [Some(&Entity { name: "x" }); 2]);
+    }
+}
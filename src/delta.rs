@@ -0,0 +1,51 @@
+use crate::{EntityId, EntityListImpl, GenWidth, IndexWidth};
+
+/// An entity type that can be updated by a delta value, e.g. a patch received over the network.
+pub trait ApplyDelta<D> {
+    fn apply(&mut self, delta: D);
+}
+
+impl<T, I: IndexWidth, G: GenWidth> EntityListImpl<T, I, G> {
+    /// Applies the delta to the entity. Returns false if the EntityId is stale, in which case the
+    /// delta is dropped.
+    pub fn apply_delta<D>(&mut self, id: EntityId<I, G>, delta: D) -> bool
+    where
+        T: ApplyDelta<D>,
+    {
+        match self.get_mut(id) {
+            Some(entity) => {
+                entity.apply(delta);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ApplyDelta;
+    use crate::EntityList;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32, i32);
+
+    impl ApplyDelta<(i32, i32)> for Position {
+        fn apply(&mut self, delta: (i32, i32)) {
+            self.0 += delta.0;
+            self.1 += delta.1;
+        }
+    }
+
+    #[test]
+    fn apply_delta() {
+        let mut el = EntityList::default();
+        let a = el.add(Position(1, 2));
+        let b = el.add(Position(0, 0));
+        assert!(el.apply_delta(a, (10, 20)));
+        assert_eq!(el[a], Position(11, 22));
+
+        el.remove(b);
+        assert!(!el.apply_delta(b, (1, 1)));
+    }
+}
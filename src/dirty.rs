@@ -0,0 +1,123 @@
+use crate::{EntityId, EntityListImpl, GenWidth, IndexWidth};
+
+impl<T, I: IndexWidth, G: GenWidth> EntityListImpl<T, I, G> {
+    /// Marks the entity as changed. Returns false if the EntityId is stale.
+    pub fn mark_dirty(&mut self, id: EntityId<I, G>) -> bool {
+        if self.get(id).is_none() {
+            return false;
+        }
+        let idx = id.id.to_usize();
+        if self.1.dirty.len() <= idx {
+            self.1.dirty.resize(idx + 1, false);
+        }
+        self.1.dirty[idx] = true;
+        true
+    }
+
+    /// Clears the dirty flag of the entity. Returns false if the EntityId is stale.
+    pub fn clear_dirty(&mut self, id: EntityId<I, G>) -> bool {
+        if self.get(id).is_none() {
+            return false;
+        }
+        if let Some(dirty) = self.1.dirty.get_mut(id.id.to_usize()) {
+            *dirty = false;
+        }
+        true
+    }
+
+    pub fn is_dirty(&self, id: EntityId<I, G>) -> bool {
+        self.get(id).is_some() && self.1.dirty.get(id.id.to_usize()) == Some(&true)
+    }
+
+    /// Iterates the EntityIds of the entities marked dirty, in slot order.
+    pub fn iter_dirty(&self) -> impl Iterator<Item = EntityId<I, G>> + '_ {
+        self.1
+            .dirty
+            .iter()
+            .enumerate()
+            .filter(|(_, dirty)| **dirty)
+            .filter_map(move |(i, _)| self.get_current_id(I::from_usize(i)))
+    }
+
+    /// Clears the dirty flags of all entities, typically at the end of a frame.
+    pub fn clear_all_dirty(&mut self) {
+        self.1.dirty.clear();
+    }
+
+    /// Splits the dirty flags like `split_off_at` does, without shifting the ones moved out.
+    pub(crate) fn split_off_dirty(&mut self, raw_index: usize) -> Vec<bool> {
+        let at = raw_index.min(self.1.dirty.len());
+        let mut ret = vec![false; at];
+        ret.extend(self.1.dirty.drain(at..));
+        ret
+    }
+
+    /// Moves the dirty flag along with an entity moved to another slot.
+    pub(crate) fn move_dirty(&mut self, from: usize, to: usize) {
+        let dirty = match self.1.dirty.get_mut(from) {
+            Some(dirty) => std::mem::take(dirty),
+            None => return,
+        };
+        if self.1.dirty.len() <= to {
+            self.1.dirty.resize(to + 1, false);
+        }
+        self.1.dirty[to] = dirty;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Entity, EntityList};
+
+    #[test]
+    fn dirty() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        assert!(el.mark_dirty(c));
+        assert!(el.mark_dirty(a));
+        assert_eq!(el.iter_dirty().collect::<Vec<_>>(), [a, c]);
+
+        assert!(el.clear_dirty(c));
+        assert!(!el.is_dirty(c));
+        el.remove(a);
+        assert!(!el.mark_dirty(a));
+        let d = el.add(Entity { name: "d" });
+        assert!(!el.is_dirty(d));
+        assert_eq!(el.iter_dirty().count(), 0);
+
+        el.mark_dirty(b);
+        el.clear_all_dirty();
+        assert_eq!(el.iter_dirty().count(), 0);
+    }
+
+    #[test]
+    fn dirty_follows_moves() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let _b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        el.mark_dirty(c);
+
+        el.remove(a);
+        let remap = el.compact();
+        let new_c = remap.iter().find(|(old, _)| *old == c).unwrap().1;
+        assert_eq!(el.iter_dirty().collect::<Vec<_>>(), [new_c]);
+
+        let remap = el.sort_unstable_by(|l, r| r.name.cmp(l.name));
+        let c = remap.iter().find(|(old, _)| *old == new_c).unwrap().1;
+        assert_eq!(el.iter_dirty().collect::<Vec<_>>(), [c]);
+    }
+
+    #[test]
+    fn dirty_split_off() {
+        let mut el: EntityList<_> = (0..4).collect();
+        let ids: Vec<_> = el.ids().collect();
+        el.mark_dirty(ids[1]);
+        el.mark_dirty(ids[2]);
+        let tail = el.split_off_at(2);
+        assert_eq!(el.iter_dirty().collect::<Vec<_>>(), [ids[1]]);
+        assert_eq!(tail.iter_dirty().collect::<Vec<_>>(), [ids[2]]);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{EntityListImpl, GenWidth, IndexWidth};
+use std::fmt::{self, Write};
+
+impl<T, I: IndexWidth, G: GenWidth> EntityListImpl<T, I, G> {
+    /// Formats the slots as a table of the slot index, the generation, the liveness and the
+    /// entity, for debugging with e.g. `eprintln!("{}", list.debug_dump())`.
+    ///
+    /// ```text
+    /// +------+-----+-------+----------------------+
+    /// | slot | gen | alive | value                |
+    /// +------+-----+-------+----------------------+
+    /// |    0 |   1 | yes   | Entity { name: "c" } |
+    /// |    1 |   0 | no    |                      |
+    /// +------+-----+-------+----------------------+
+    /// ```
+    pub fn debug_dump(&self) -> String
+    where
+        T: fmt::Debug,
+    {
+        self.dump_table(|entity| format!("{:?}", entity))
+    }
+
+    /// Same as [`EntityList::debug_dump`](crate::EntityList::debug_dump) for entity types
+    /// without Debug, showing `?` for alive entities.
+    pub fn debug_dump_no_value(&self) -> String {
+        self.dump_table(|_| "?".to_string())
+    }
+
+    fn dump_table(&self, value: impl Fn(&T) -> String) -> String {
+        let header = ["slot", "gen", "alive", "value"];
+        let rows: Vec<[String; 4]> = self
+            .0
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                [
+                    i.to_string(),
+                    entry.gen.to_string(),
+                    if entry.entity.is_some() { "yes" } else { "no" }.to_string(),
+                    entry.entity.as_ref().map(&value).unwrap_or_default(),
+                ]
+            })
+            .collect();
+
+        let mut widths = header.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        let mut ret = String::new();
+        let separator: String = widths
+            .iter()
+            .map(|width| format!("+{}", "-".repeat(width + 2)))
+            .collect::<String>()
+            + "+\n";
+        ret += &separator;
+        for (cell, width) in header.iter().zip(&widths) {
+            let _ = write!(ret, "| {:<1$} ", cell, width);
+        }
+        ret += "|\n";
+        ret += &separator;
+        for row in &rows {
+            // Numbers are right-aligned and the rest are left-aligned.
+            let _ = write!(ret, "| {:>1$} ", row[0], widths[0]);
+            let _ = write!(ret, "| {:>1$} ", row[1], widths[1]);
+            let _ = write!(ret, "| {:<1$} ", row[2], widths[2]);
+            let _ = write!(ret, "| {:<1$} ", row[3], widths[3]);
+            ret += "|\n";
+        }
+        ret += &separator;
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Entity, EntityList};
+
+    #[test]
+    fn debug_dump() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        el.add(Entity { name: "b" });
+        el.remove(a);
+        el.add(Entity { name: "c" });
+        let b = el.get_current_id(1).unwrap();
+        el.remove(b);
+
+        assert_eq!(
+            el.debug_dump(),
+            "\
++------+-----+-------+----------------------+
+| slot | gen | alive | value                |
++------+-----+-------+----------------------+
+|    0 |   1 | yes   | Entity { name: \"c\" } |
+|    1 |   0 | no    |                      |
++------+-----+-------+----------------------+
+"
+        );
+        let lines: Vec<_> = el
+            .debug_dump_no_value()
+            .lines()
+            .map(str::to_owned)
+            .collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[3], "|    0 |   1 | yes   | ?     |");
+        assert_eq!(lines[4], "|    1 |   0 | no    |       |");
+    }
+}
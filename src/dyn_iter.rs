@@ -1,6 +1,6 @@
 use std::iter;
 
-pub(crate) trait DynIter {
+pub trait DynIter {
     type Item: ?Sized;
     fn dyn_iter(&self) -> Box<dyn Iterator<Item = &Self::Item> + '_>;
     fn as_dyn_iter(&self) -> &dyn DynIter<Item = Self::Item>;
@@ -18,7 +18,7 @@ where
     }
 }
 
-pub(crate) trait DynIterMut: DynIter {
+pub trait DynIterMut: DynIter {
     fn dyn_iter_mut(&mut self) -> Box<dyn Iterator<Item = &mut Self::Item> + '_>;
 }
 impl<T, Item> DynIterMut for T
@@ -31,7 +31,7 @@ where
     }
 }
 
-pub(crate) struct MutRef<'r, T: ?Sized>(pub &'r mut T);
+pub struct MutRef<'r, T: ?Sized>(pub &'r mut T);
 impl<'a, 'r, T: ?Sized> IntoIterator for &'a MutRef<'r, T>
 where
     &'a T: IntoIterator,
@@ -53,7 +53,7 @@ where
     }
 }
 
-pub(crate) struct Ref<'r, T: ?Sized>(pub &'r T);
+pub struct Ref<'r, T: ?Sized>(pub &'r T);
 impl<'a, 'r, T: ?Sized> IntoIterator for &'a Ref<'r, T>
 where
     &'a T: IntoIterator,
@@ -65,7 +65,7 @@ where
     }
 }
 
-pub(crate) struct Chained<S, T>(pub S, pub T);
+pub struct Chained<S, T>(pub S, pub T);
 impl<'a, S, T, Item: 'a> IntoIterator for &'a Chained<S, T>
 where
     &'a S: IntoIterator<Item = &'a Item>,
@@ -75,7 +75,7 @@ where
         iter::Chain<<&'a S as IntoIterator>::IntoIter, <&'a T as IntoIterator>::IntoIter>;
     type Item = &'a Item;
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter().chain(self.1.into_iter())
+        self.0.into_iter().chain(&self.1)
     }
 }
 impl<'a, S, T, Item: 'a> IntoIterator for &'a mut Chained<S, T>
@@ -87,11 +87,12 @@ where
         iter::Chain<<&'a mut S as IntoIterator>::IntoIter, <&'a mut T as IntoIterator>::IntoIter>;
     type Item = &'a mut Item;
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter().chain(self.1.into_iter())
+        self.0.into_iter().chain(&mut self.1)
     }
 }
 
 #[test]
+#[allow(clippy::map_clone, unused_variables)]
 fn test_dyn_iter() {
     let v = vec![0, 1, 2, 3, 4];
 
@@ -0,0 +1,106 @@
+use crate::{EntityId, EntityList};
+
+/// A view into a slot of an EntityList, obtained by [`EntityList::entry`].
+pub enum Entry<'a, T> {
+    Occupied(OccupiedEntry<'a, T>),
+    Vacant(VacantEntry<'a, T>),
+}
+
+impl<'a, T> Entry<'a, T> {
+    /// Returns the entity if the EntityId is alive, or adds the given entity otherwise.
+    pub fn or_insert(self, entity: T) -> &'a mut T {
+        self.or_insert_with(|| entity)
+    }
+
+    pub fn or_insert_with(self, f: impl FnOnce() -> T) -> &'a mut T {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert(f()).1,
+        }
+    }
+}
+
+/// The EntityId points to an alive entity.
+pub struct OccupiedEntry<'a, T> {
+    list: &'a mut EntityList<T>,
+    id: EntityId,
+}
+
+impl<'a, T> OccupiedEntry<'a, T> {
+    pub fn id(&self) -> EntityId {
+        self.id
+    }
+
+    pub fn get(&self) -> &T {
+        &self.list[self.id]
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.list[self.id]
+    }
+
+    pub fn into_mut(self) -> &'a mut T {
+        &mut self.list[self.id]
+    }
+
+    pub fn remove(self) -> T {
+        self.list.remove(self.id).unwrap()
+    }
+}
+
+/// The EntityId is stale or has never been issued. Inserting an entity assigns a new slot,
+/// since the original EntityId cannot be revived.
+pub struct VacantEntry<'a, T> {
+    list: &'a mut EntityList<T>,
+}
+
+impl<'a, T> VacantEntry<'a, T> {
+    pub fn insert(self, entity: T) -> (EntityId, &'a mut T) {
+        let id = self.list.add(entity);
+        (id, &mut self.list[id])
+    }
+}
+
+impl<T> EntityList<T> {
+    pub fn entry(&mut self, id: EntityId) -> Entry<'_, T> {
+        if self.get(id).is_some() {
+            Entry::Occupied(OccupiedEntry { list: self, id })
+        } else {
+            Entry::Vacant(VacantEntry { list: self })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Entry;
+    use crate::{Entity, EntityList};
+
+    #[test]
+    fn entry() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+
+        el.entry(a).or_insert_with(|| Entity { name: "x" }).name = "A";
+        assert_eq!(el.get(a), Some(&Entity { name: "A" }));
+        assert_eq!(el.len(), 1);
+
+        match el.entry(a) {
+            Entry::Occupied(entry) => {
+                assert_eq!(entry.id(), a);
+                assert_eq!(entry.remove(), Entity { name: "A" });
+            }
+            Entry::Vacant(_) => panic!("a should be occupied"),
+        }
+
+        let b = match el.entry(a) {
+            Entry::Occupied(_) => panic!("a should be vacant"),
+            Entry::Vacant(entry) => entry.insert(Entity { name: "b" }).0,
+        };
+        assert_ne!(a, b);
+        assert_eq!(el.get(b), Some(&Entity { name: "b" }));
+
+        el.entry(a).or_insert(Entity { name: "c" });
+        assert_eq!(el.len(), 2);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{EntityId, EntityList};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A snapshot mapping from a secondary key to EntityIds, built by [`EntityList::build_index`].
+///
+/// The index does not follow the list; [`EntityIndex::needs_rebuild`] tells if entities have been
+/// added, removed or moved since it was built. Keys changed through mutable access to the entities
+/// are not detected, so rebuild the index after such changes too.
+///
+/// If multiple entities share a key, the last one in iteration order wins.
+#[derive(Clone, Debug)]
+pub struct EntityIndex<K: Eq + Hash> {
+    map: HashMap<K, EntityId>,
+    generation: u64,
+}
+
+impl<K: Eq + Hash> EntityIndex<K> {
+    /// Returns the EntityId stored for the key if it is still alive in the list.
+    pub fn lookup<T>(&self, k: &K, list: &EntityList<T>) -> Option<EntityId> {
+        let id = *self.map.get(k)?;
+        list.get(id)?;
+        Some(id)
+    }
+
+    pub fn needs_rebuild<T>(&self, list: &EntityList<T>) -> bool {
+        self.generation != list.1.version
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl<T> EntityList<T> {
+    /// Builds an [`EntityIndex`] from the key of every alive entity for lookups in O(1).
+    pub fn build_index<K: Eq + Hash + Clone, F: Fn(&T) -> K>(&self, key: F) -> EntityIndex<K> {
+        EntityIndex {
+            map: self.iter().map(|(id, entity)| (key(entity), id)).collect(),
+            generation: self.1.version,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Entity, EntityList};
+
+    #[test]
+    fn build_index() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let index = el.build_index(|e| e.name);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.lookup(&"a", &el), Some(a));
+        assert_eq!(index.lookup(&"b", &el), Some(b));
+        assert_eq!(index.lookup(&"c", &el), None);
+        assert!(!index.needs_rebuild(&el));
+
+        el[a].name = "aa";
+        assert!(!index.needs_rebuild(&el));
+
+        el.remove(b);
+        assert!(index.needs_rebuild(&el));
+        assert_eq!(index.lookup(&"b", &el), None);
+
+        let c = el.add(Entity { name: "c" });
+        let index = el.build_index(|e| e.name);
+        assert_eq!(index.lookup(&"aa", &el), Some(a));
+        assert_eq!(index.lookup(&"c", &el), Some(c));
+        assert!(!index.needs_rebuild(&el));
+
+        el.compact();
+        assert!(index.needs_rebuild(&el));
+
+        let index = el.build_index(|e| e.name);
+        let el = el.filter_map(|_, e| Some(e));
+        assert!(index.needs_rebuild(&el));
+    }
+}
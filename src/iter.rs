@@ -1,15 +1,23 @@
 use crate::dyn_iter::{DynIter, DynIterMut};
-use crate::{Entity, EntityEntry, EntityId, EntityList};
-use smallvec::{SmallVec, smallvec};
+use crate::{EntityEntry, EntityId, EntityList};
+use smallvec::{smallvec, SmallVec};
 // use std::iter::IntoIterator;
 
-#[derive(Default)]
-pub struct EntitySlice<'a> {
+pub struct EntitySlice<'a, T> {
     start: usize,
-    slice: &'a mut [EntityEntry],
+    slice: &'a mut [EntityEntry<T>],
 }
 
-impl<'a> EntitySlice<'a> {
+impl<'a, T> Default for EntitySlice<'a, T> {
+    fn default() -> Self {
+        Self {
+            start: 0,
+            slice: &mut [],
+        }
+    }
+}
+
+impl<'a, T> EntitySlice<'a, T> {
     /// A "dirty" clone that takes mutable reference.
     /// Because it requires mutable reference to self, we cannot implement Clone trait.
     ///
@@ -20,7 +28,7 @@ impl<'a> EntitySlice<'a> {
     /// Lifetime annotation is still a bit weird, it should return EntitySlice<'a> since the
     /// underlying EntityEntry lifetime should not change by making a slice to it, but
     /// somehow it fails to compile if I do.
-    pub fn clone(&mut self) -> EntitySlice {
+    pub fn clone(&mut self) -> EntitySlice<'_, T> {
         EntitySlice {
             start: self.start,
             slice: self.slice,
@@ -47,20 +55,20 @@ impl<'a> EntitySlice<'a> {
 ///
 /// It can access internal object in O(n) where n is the number of slices, not the number of objects.
 /// It is convenient when you want to have mutable reference to two elements in the array at the same time.
-pub struct EntityDynIter<'a>(SmallVec<[EntitySlice<'a>; 2]>);
+pub struct EntityDynIter<'a, T>(SmallVec<[EntitySlice<'a, T>; 2]>);
 
-impl<'a> EntityDynIter<'a> {
-    pub(crate) fn new_all(source: &'a mut EntityList) -> Self {
+impl<'a, T> EntityDynIter<'a, T> {
+    pub fn new_all(source: &'a mut EntityList<T>) -> Self {
         Self(smallvec![EntitySlice {
             start: 0,
             slice: &mut source.0,
         }])
     }
 
-    pub(crate) fn new_split(
-        source: &'a mut EntityList,
+    pub fn new_split(
+        source: &'a mut EntityList<T>,
         split_idx: usize,
-    ) -> Option<(&'a mut EntityEntry, Self)> {
+    ) -> Option<(&'a mut EntityEntry<T>, Self)> {
         let (left, right) = source.0.split_at_mut(split_idx);
         let (center, right) = right.split_first_mut()?;
         Some((
@@ -78,7 +86,61 @@ impl<'a> EntityDynIter<'a> {
         ))
     }
 
-    pub(crate) fn dyn_iter_id(&self) -> impl Iterator<Item = (EntityId, &Entity)> + '_ {
+    /// Generalization of `new_split` that splits out multiple entries at once.
+    ///
+    /// `split_idxs` must be strictly increasing and in bounds, otherwise returns None.
+    pub fn new_multi_split(
+        source: &'a mut EntityList<T>,
+        split_idxs: &[usize],
+    ) -> Option<(Vec<&'a mut EntityEntry<T>>, Self)> {
+        let mut centers = Vec::with_capacity(split_idxs.len());
+        let mut slices = SmallVec::new();
+        let mut start = 0;
+        let mut rest: &'a mut [EntityEntry<T>] = &mut source.0;
+        for &idx in split_idxs {
+            if idx < start || rest.len() <= idx - start {
+                return None;
+            }
+            let (left, right) = std::mem::take(&mut rest).split_at_mut(idx - start);
+            let (center, right) = right.split_first_mut()?;
+            slices.push(EntitySlice { start, slice: left });
+            centers.push(center);
+            start = idx + 1;
+            rest = right;
+        }
+        slices.push(EntitySlice { start, slice: rest });
+        Some((centers, Self(slices)))
+    }
+
+    /// Splits out the entries of the given EntityIds, and returns them in the same order.
+    ///
+    /// A stale EntityId, or a duplicate of an earlier one, gets None since it cannot be borrowed.
+    pub fn new_from_ids(
+        source: &'a mut EntityList<T>,
+        excluded_ids: &[EntityId],
+    ) -> (Vec<Option<&'a mut EntityEntry<T>>>, Self) {
+        // Pairs of slot index and the position in excluded_ids
+        let mut idxs: Vec<_> = excluded_ids
+            .iter()
+            .enumerate()
+            .filter(|(_, id)| source.get(**id).is_some())
+            .map(|(pos, id)| (id.id as usize, pos))
+            .collect();
+        idxs.sort_unstable();
+        idxs.dedup_by_key(|(idx, _)| *idx);
+
+        let split_idxs: Vec<_> = idxs.iter().map(|(idx, _)| *idx).collect();
+        let (centers, this) = Self::new_multi_split(source, &split_idxs)
+            .expect("indices of alive entities should be valid split points");
+
+        let mut ret: Vec<_> = excluded_ids.iter().map(|_| None).collect();
+        for ((_, pos), center) in idxs.into_iter().zip(centers) {
+            ret[pos] = Some(center);
+        }
+        (ret, this)
+    }
+
+    pub fn dyn_iter_id(&self) -> impl Iterator<Item = (EntityId, &T)> + '_ {
         self.0
             .iter()
             .flat_map(move |slice| {
@@ -103,7 +165,7 @@ impl<'a> EntityDynIter<'a> {
     // Couldn't get this to compile
     // pub(crate) fn dyn_iter_mut_id(
     //     &mut self,
-    // ) -> impl Iterator<Item = (EntityId, &mut Entity)> + '_ {
+    // ) -> impl Iterator<Item = (EntityId, &mut T)> + '_ {
     //     self.0
     //         .iter_mut()
     //         .flat_map(move |slice| {
@@ -125,7 +187,27 @@ impl<'a> EntityDynIter<'a> {
     //         })
     // }
 
-    pub(crate) fn exclude(&mut self, id: EntityId) -> Option<&mut Entity> {
+    /// Low-level escape hatch to access an entry by the raw slot index, e.g. the one stored in an
+    /// external spatial index, in O(n) where n is the number of slices.
+    ///
+    /// It does not check the generation; inspect [`EntityEntry::generation`] and
+    /// [`EntityEntry::value`] if needed. Returns None if the index is excluded from this view.
+    pub fn get_by_raw_index(&self, idx: usize) -> Option<&EntityEntry<T>> {
+        self.0
+            .iter()
+            .find(|slice| slice.start <= idx && idx < slice.start + slice.slice.len())
+            .map(|slice| &slice.slice[idx - slice.start])
+    }
+
+    /// Mutable version of [`EntityDynIter::get_by_raw_index`].
+    pub fn get_by_raw_index_mut(&mut self, idx: usize) -> Option<&mut EntityEntry<T>> {
+        self.0
+            .iter_mut()
+            .find(|slice| slice.start <= idx && idx < slice.start + slice.slice.len())
+            .map(|slice| &mut slice.slice[idx - slice.start])
+    }
+
+    pub fn exclude(&mut self, id: EntityId) -> Option<&mut T> {
         let idx = id.id as usize;
         if let Some((slice_idx, _)) = self
             .0
@@ -155,10 +237,10 @@ impl<'a> EntityDynIter<'a> {
         }
     }
 
-    pub(crate) fn exclude_copy<'b>(
+    pub fn exclude_copy<'b>(
         &'b mut self,
         id: EntityId,
-    ) -> Option<(Option<&'b mut Entity>, EntityDynIter<'b>)>
+    ) -> Option<(Option<&'b mut T>, EntityDynIter<'b, T>)>
     where
         'a: 'b,
     {
@@ -209,8 +291,8 @@ impl<'a> EntityDynIter<'a> {
     }
 }
 
-impl<'a> DynIter for EntityDynIter<'a> {
-    type Item = Entity;
+impl<'a, T> DynIter for EntityDynIter<'a, T> {
+    type Item = T;
     fn dyn_iter(&self) -> Box<dyn Iterator<Item = &Self::Item> + '_> {
         Box::new(
             self.0
@@ -223,7 +305,7 @@ impl<'a> DynIter for EntityDynIter<'a> {
     }
 }
 
-impl<'a> DynIterMut for EntityDynIter<'a> {
+impl<'a, T> DynIterMut for EntityDynIter<'a, T> {
     fn dyn_iter_mut(&mut self) -> Box<dyn Iterator<Item = &mut Self::Item> + '_> {
         Box::new(
             self.0
@@ -382,4 +464,73 @@ mod tests {
             assert_eq!(iter.next(), None);
         }
     }
+
+    #[test]
+    fn raw_index() {
+        let mut el = EntityList::default();
+        let _a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let _c = el.add(Entity { name: "c" });
+        el.remove(b);
+        let b = el.add(Entity { name: "B" });
+
+        let (_, mut dyn_iter) = EntityDynIter::new_split(&mut el, 0).unwrap();
+        assert!(dyn_iter.get_by_raw_index(0).is_none());
+        assert!(dyn_iter.get_by_raw_index(3).is_none());
+
+        let entry = dyn_iter.get_by_raw_index(1).unwrap();
+        assert_eq!(entry.generation(), b.gen);
+        assert_eq!(entry.value().map(|e| e.name), Some("B"));
+
+        if let Some(e) = dyn_iter.get_by_raw_index_mut(2).unwrap().value_mut() {
+            e.name = "C";
+        }
+        assert_eq!(
+            dyn_iter
+                .get_by_raw_index(2)
+                .unwrap()
+                .value()
+                .map(|e| e.name),
+            Some("C")
+        );
+    }
+
+    #[test]
+    fn multi_split() {
+        let mut el = EntityList::default();
+        for name in ["a", "b", "c", "d", "e"] {
+            el.add(Entity { name });
+        }
+        assert!(EntityDynIter::new_multi_split(&mut el, &[3, 1]).is_none());
+        assert!(EntityDynIter::new_multi_split(&mut el, &[1, 5]).is_none());
+
+        let (centers, dyn_iter) = EntityDynIter::new_multi_split(&mut el, &[0, 1, 3]).unwrap();
+        let names: Vec<_> = centers.iter().map(|e| e.value().unwrap().name).collect();
+        assert_eq!(names, ["a", "b", "d"]);
+        let mut iter = dyn_iter.dyn_iter();
+        assert_eq!(iter.next().map(|e| e.name), Some("c"));
+        assert_eq!(iter.next().map(|e| e.name), Some("e"));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn split_from_ids() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        let d = el.add(Entity { name: "d" });
+        el.remove(b);
+
+        let (entries, dyn_iter) = EntityDynIter::new_from_ids(&mut el, &[d, b, a, d]);
+        let names: Vec<_> = entries
+            .iter()
+            .map(|e| e.as_ref().map(|e| e.value().unwrap().name))
+            .collect();
+        assert_eq!(names, [Some("d"), None, Some("a"), None]);
+
+        let mut iter = dyn_iter.dyn_iter_id();
+        assert_eq!(iter.next().map(|(id, e)| (id, e.name)), Some((c, "c")));
+        assert_eq!(iter.next(), None);
+    }
 }
@@ -1,26 +1,197 @@
-mod dyn_iter;
+#[cfg(feature = "bytemuck")]
+pub mod codec;
+mod cross;
+mod cursor;
+mod delta;
+#[cfg(feature = "dirty-tracking")]
+mod dirty;
+mod dump;
+/// The traits to iterate an [`EntityDynIter`], which must be in scope to call `dyn_iter` and
+/// `dyn_iter_mut` on it.
+pub mod dyn_iter;
+mod entry;
+mod index;
 mod iter;
+mod meta;
+#[cfg(feature = "observer")]
+mod observer;
+mod paged;
+#[cfg(feature = "rayon")]
+mod par_iter;
+mod remap;
+mod secondary;
+mod snapshot;
+#[cfg(feature = "sync")]
+pub mod sync;
+mod validate;
+mod weak;
+mod width;
 
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::ops::{Bound, Index, IndexMut, RangeBounds};
+use std::panic::{RefUnwindSafe, UnwindSafe};
+
+pub use cross::cross_borrow_mut;
+pub use cursor::{EntityCursor, EntityCursorMut};
+pub use delta::ApplyDelta;
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
+pub use index::EntityIndex;
 pub use iter::EntityDynIter;
+pub use meta::EntityListWithMeta;
+pub use paged::PagedEntityList;
+pub use remap::{HasEntityIds, NoEntityIds};
+pub use secondary::SecondaryMap;
+pub use snapshot::{EntityListDiff, EntityListSnapshot};
+pub use validate::{ValidationError, ValidationKind};
+pub use weak::WeakEntityId;
+pub use width::{GenWidth, IndexWidth};
+
+/// The index type `I` and the generation type `G` must match the list that issued the EntityId,
+/// so EntityIds of lists with different [`IndexWidth`]s or [`GenWidth`]s cannot be mixed up.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EntityId<I = u32, G = u32> {
+    #[cfg_attr(feature = "serde", serde(rename = "index"))]
+    id: I,
+    #[cfg_attr(feature = "serde", serde(rename = "generation"))]
+    gen: G,
+}
+
+impl<I: Copy, G: Copy> EntityId<I, G> {
+    /// The raw slot index, as accepted by e.g. [`EntityList::get_current_id`].
+    pub fn index(&self) -> I {
+        self.id
+    }
+
+    pub fn generation(&self) -> G {
+        self.gen
+    }
+}
+
+/// Formats as `#index:gen`.
+impl<I: fmt::Display, G: fmt::Display> fmt::Display for EntityId<I, G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}:{}", self.id, self.gen)
+    }
+}
+
+/// EntityId of a [`SmallEntityList`], which takes 4 bytes instead of 8.
+pub type SmallEntityId = EntityId<u16, u16>;
+
+/// The reason why `EntityList::try_remove` failed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RemoveError {
+    /// The EntityId was not issued by this list.
+    SlotOutOfBounds,
+    /// The slot has been reused by another entity, i.e. the EntityId is stale.
+    GenerationMismatch { current_gen: u32 },
+    /// The entity has already been removed.
+    SlotAlreadyEmpty,
+}
+
+impl fmt::Display for RemoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::SlotOutOfBounds => write!(f, "slot out of bounds"),
+            Self::GenerationMismatch { current_gen } => {
+                write!(f, "generation mismatch (current: {})", current_gen)
+            }
+            Self::SlotAlreadyEmpty => write!(f, "slot already empty"),
+        }
+    }
+}
 
+impl std::error::Error for RemoveError {}
+
+/// The EntityId that made `EntityList::from_hashmap` fail.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub struct EntityId {
-    id: u32,
-    gen: u32,
+pub enum FromHashMapError<I = u32, G = u32> {
+    /// The index is at or beyond the limit given to `from_hashmap_with_max_entities`.
+    IndexOutOfRange(EntityId<I, G>),
+    /// The generation is `G::MAX`, which is never issued.
+    ExhaustedGeneration(EntityId<I, G>),
+}
+
+impl<I: fmt::Display, G: fmt::Display> fmt::Display for FromHashMapError<I, G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::IndexOutOfRange(id) => write!(f, "index of {} out of range", id),
+            Self::ExhaustedGeneration(id) => write!(f, "exhausted generation of {}", id),
+        }
+    }
+}
+
+impl<I: fmt::Display + fmt::Debug, G: fmt::Display + fmt::Debug> std::error::Error
+    for FromHashMapError<I, G>
+{
+}
+
+/// The reason why `EntityList::try_add_stable` did not place the entity at the preferred EntityId.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum AddStableError<T, I = u32, G = u32> {
+    /// The preferred EntityId was not available, so the entity was added with this new one.
+    Relocated(EntityId<I, G>),
+    /// The list is full, so the entity is given back.
+    Full(T),
+}
+
+impl<T, I: fmt::Display, G: fmt::Display> fmt::Display for AddStableError<T, I, G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Relocated(id) => write!(f, "relocated to {}", id),
+            Self::Full(_) => write!(f, "list is full"),
+        }
+    }
+}
+
+impl<T: fmt::Debug, I: fmt::Display + fmt::Debug, G: fmt::Display + fmt::Debug> std::error::Error
+    for AddStableError<T, I, G>
+{
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[cfg(test)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct Entity {
     name: &'static str,
 }
 
-struct EntityEntry {
-    gen: u32,
-    entity: Option<Entity>,
+/// A slot in the EntityList, which may or may not hold an entity.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EntityEntry<T, G = u32> {
+    #[cfg_attr(feature = "serde", serde(rename = "generation"))]
+    gen: G,
+    entity: Option<T>,
 }
 
-impl EntityEntry {
-    fn get_mut(&mut self, id: EntityId) -> Option<&mut Entity> {
+impl<T, G: GenWidth> EntityEntry<T, G> {
+    /// The current generation of the slot. It matches the EntityId's generation only if the
+    /// EntityId was issued for the current occupant.
+    pub fn generation(&self) -> G {
+        self.gen
+    }
+
+    pub fn value(&self) -> Option<&T> {
+        self.entity.as_ref()
+    }
+
+    pub fn value_mut(&mut self) -> Option<&mut T> {
+        self.entity.as_mut()
+    }
+
+    /// The generation for a new entity in this slot, or None if the slot is alive or exhausted.
+    fn reusable_gen(&self) -> Option<G> {
+        if self.entity.is_some() {
+            return None;
+        }
+        self.gen.checked_next_gen()
+    }
+
+    fn get_mut<I>(&mut self, id: EntityId<I, G>) -> Option<&mut T> {
         if self.gen == id.gen {
             self.entity.as_mut()
         } else {
@@ -29,40 +200,798 @@ impl EntityEntry {
     }
 }
 
-#[derive(Default)]
-pub struct EntityList(Vec<EntityEntry>);
+/// The list of entities with the slot index type `I` and the generation type `G`. Use it through
+/// the aliases [`EntityList`] (with `u32` for both), [`EntityList8`], [`EntityList16`] and
+/// [`SmallEntityList`].
+///
+/// Only the core API is generic over the index and generation types; the rest, e.g.
+/// [`EntityCursor`] or [`SecondaryMap`], is for [`EntityList`].
+///
+/// With the `serde` feature, dead slots are serialized too, so that the generations and thus
+/// the EntityIds are preserved. Deserialization fails if there are more slots than `I` can index
+/// or the bookkeeping does not pass [`EntityList::validate`].
+///
+/// Cloning copies the slots, so the EntityIds resolve identically in the clone, and the settings,
+/// but not the callbacks registered by `on_compact`, `on_add` or `on_remove`, since they usually
+/// refer to the storage of the original list. Register them again on the clone if needed.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EntityListImpl<T, I = u32, G = u32>(Vec<EntityEntry<T, G>>, ListConfig<T, I, G>);
+
+pub type EntityList<T> = EntityListImpl<T, u32, u32>;
+/// EntityList with `u8` generations, for slots that are rarely reused.
+pub type EntityList8<T> = EntityListImpl<T, u32, u8>;
+/// EntityList with `u16` generations, for slots that are rarely reused.
+pub type EntityList16<T> = EntityListImpl<T, u32, u16>;
+/// Same as [`EntityList`], for symmetry with [`EntityList8`] and [`EntityList16`].
+pub type EntityList32<T> = EntityListImpl<T, u32, u32>;
+/// EntityList of up to 65536 entities with `u16` indices and generations, whose
+/// [`SmallEntityId`]s take half the size of EntityIds.
+pub type SmallEntityList<T> = EntityListImpl<T, u16, u16>;
+
+#[cfg(feature = "serde")]
+impl<'de, T, I, G> serde::Deserialize<'de> for EntityListImpl<T, I, G>
+where
+    T: serde::Deserialize<'de>,
+    I: IndexWidth + serde::Deserialize<'de>,
+    G: GenWidth + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let (slots, config): (Vec<EntityEntry<T, G>>, ListConfig<T, I, G>) =
+            serde::Deserialize::deserialize(deserializer)?;
+        if I::MAX_SLOTS < slots.len() {
+            return Err(D::Error::custom(format_args!(
+                "{} slots exceed the {} slots the index type can index",
+                slots.len(),
+                I::MAX_SLOTS
+            )));
+        }
+        let list = Self(slots, config);
+        list.validate().map_err(D::Error::custom)?;
+        Ok(list)
+    }
+}
+
+impl<T, I, G: Default> Default for EntityListImpl<T, I, G> {
+    fn default() -> Self {
+        Self(vec![], ListConfig::default())
+    }
+}
+
+/// The (old, new) EntityIds of an entity moved to another slot.
+type MovedId<I, G> = (EntityId<I, G>, EntityId<I, G>);
+
+/// The (prev, current, next) EntityIds yielded by `iter_with_neighbors`.
+type Neighbors<I, G> = (
+    Option<EntityId<I, G>>,
+    EntityId<I, G>,
+    Option<EntityId<I, G>>,
+);
+
+/// Two distinct slots returned by `get_two_entries_mut`.
+type EntryPair<'a, T, G> = (&'a mut EntityEntry<T, G>, &'a mut EntityEntry<T, G>);
+
+type CompactCallback<I, G> =
+    Box<dyn FnMut(&[MovedId<I, G>]) + Send + Sync + UnwindSafe + RefUnwindSafe>;
+
+/// The settings and bookkeeping of an EntityList besides the slots.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+struct ListConfig<T, I, G> {
+    max_entities: Option<usize>,
+    tombstone_limit: Option<usize>,
+    /// The number of alive slots, so that `len` does not scan the slots.
+    alive: usize,
+    /// The indices of the dead slots that `add` reuses, last in first out. Exhausted slots are
+    /// left out.
+    #[cfg_attr(
+        feature = "serde",
+        serde(bound(
+            serialize = "I: serde::Serialize",
+            deserialize = "I: serde::Deserialize<'de>"
+        ))
+    )]
+    free: Vec<I>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    on_compact: Option<CompactCallback<I, G>>,
+    /// Dirty flags indexed by slots, which may be shorter than the slots. Dead slots are never
+    /// dirty.
+    #[cfg(feature = "dirty-tracking")]
+    dirty: Vec<bool>,
+    #[cfg(feature = "observer")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    observers: observer::Observers<T, I, G>,
+    /// Bumped whenever entities are added, removed or moved, so that an [`EntityIndex`] can tell
+    /// if it is outdated.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    version: u64,
+    /// The generation of the slots pushed at the end, which is above the generations of the
+    /// slots dropped from the end by e.g. `compact`, so that their stale EntityIds cannot alias
+    /// the new entities.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            bound(
+                serialize = "G: serde::Serialize",
+                deserialize = "G: serde::Deserialize<'de> + Default"
+            )
+        )
+    )]
+    gen_floor: G,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _marker: PhantomData<fn(&T, I)>,
+}
+
+impl<T, I, G: Default> Default for ListConfig<T, I, G> {
+    fn default() -> Self {
+        Self {
+            max_entities: None,
+            tombstone_limit: None,
+            alive: 0,
+            free: vec![],
+            on_compact: None,
+            #[cfg(feature = "dirty-tracking")]
+            dirty: vec![],
+            #[cfg(feature = "observer")]
+            observers: Default::default(),
+            version: 0,
+            gen_floor: G::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, I, G> ListConfig<T, I, G> {
+    /// Carries the settings and bookkeeping over to a list of another entity type, dropping the
+    /// observers that take the old entity type.
+    fn with_entity_type<U>(self) -> ListConfig<U, I, G> {
+        ListConfig {
+            max_entities: self.max_entities,
+            tombstone_limit: self.tombstone_limit,
+            alive: self.alive,
+            free: self.free,
+            on_compact: self.on_compact,
+            #[cfg(feature = "dirty-tracking")]
+            dirty: self.dirty,
+            #[cfg(feature = "observer")]
+            observers: Default::default(),
+            version: self.version,
+            gen_floor: self.gen_floor,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// The callbacks are not cloned, since they usually refer to the storage of the original list.
+impl<T, I: Clone, G: Clone> Clone for ListConfig<T, I, G> {
+    fn clone(&self) -> Self {
+        Self {
+            max_entities: self.max_entities,
+            tombstone_limit: self.tombstone_limit,
+            alive: self.alive,
+            free: self.free.clone(),
+            on_compact: None,
+            #[cfg(feature = "dirty-tracking")]
+            dirty: self.dirty.clone(),
+            #[cfg(feature = "observer")]
+            observers: Default::default(),
+            version: self.version,
+            gen_floor: self.gen_floor.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, I: IndexWidth, G: GenWidth> FromIterator<T> for EntityListImpl<T, I, G> {
+    /// Builds the backing Vec directly in one pass.
+    ///
+    /// Panics if there are more entities than the index type `I` can index.
+    fn from_iter<It: IntoIterator<Item = T>>(iter: It) -> Self {
+        let slots: Vec<_> = iter
+            .into_iter()
+            .map(|entity| EntityEntry {
+                gen: G::default(),
+                entity: Some(entity),
+            })
+            .collect();
+        assert!(
+            slots.len() <= I::MAX_SLOTS,
+            "EntityList: slot index overflow"
+        );
+        let config = ListConfig {
+            alive: slots.len(),
+            ..ListConfig::default()
+        };
+        Self(slots, config)
+    }
+}
+
+impl<T, I: IndexWidth, G: GenWidth> Extend<T> for EntityListImpl<T, I, G> {
+    fn extend<It: IntoIterator<Item = T>>(&mut self, iter: It) {
+        for entity in iter {
+            self.add(entity);
+        }
+    }
+}
+
+impl<T, I: IndexWidth, G: GenWidth> Index<EntityId<I, G>> for EntityListImpl<T, I, G> {
+    type Output = T;
+
+    /// Panics if the EntityId is stale.
+    #[track_caller]
+    fn index(&self, id: EntityId<I, G>) -> &T {
+        match self.get(id) {
+            Some(entity) => entity,
+            None => panic!("EntityList: no entity with id {}", id),
+        }
+    }
+}
+
+impl<T, I: IndexWidth, G: GenWidth> IndexMut<EntityId<I, G>> for EntityListImpl<T, I, G> {
+    #[track_caller]
+    fn index_mut(&mut self, id: EntityId<I, G>) -> &mut T {
+        match self.get_mut(id) {
+            Some(entity) => entity,
+            None => panic!("EntityList: no entity with id {}", id),
+        }
+    }
+}
+
+impl<T, I: IndexWidth, G: GenWidth> EntityListImpl<T, I, G> {
+    #[must_use = "creating an EntityList without using it is likely a bug"]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty list with room for `capacity` slots without reallocation.
+    #[must_use = "creating an EntityList without using it is likely a bug"]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(Vec::with_capacity(capacity), ListConfig::default())
+    }
+
+    /// Like `collect()`, but also returns the EntityIds of the entities in the iteration order.
+    pub fn from_iter_with_ids(iter: impl IntoIterator<Item = T>) -> (Self, Vec<EntityId<I, G>>) {
+        let ret: Self = iter.into_iter().collect();
+        let ids = (0..ret.0.len())
+            .map(|i| EntityId {
+                id: I::from_usize(i),
+                gen: G::default(),
+            })
+            .collect();
+        (ret, ids)
+    }
+
+    /// Builds a list from a Vec in one pass. The entities get sequential slots with generation
+    /// 0, whose EntityIds are returned in the same order.
+    pub fn from_entities(entities: Vec<T>) -> (Self, Vec<EntityId<I, G>>) {
+        Self::from_iter_with_ids(entities)
+    }
+
+    pub fn from_entities_no_ids(entities: Vec<T>) -> Self {
+        entities.into_iter().collect()
+    }
+
+    /// Limits the number of alive entities to `max`.
+    ///
+    /// `try_add` fails once the limit is reached, while `add` panics in debug builds, or in
+    /// release builds too if the `strict-limits` feature is enabled.
+    pub fn with_max_entities(mut self, max: usize) -> Self {
+        self.1.max_entities = Some(max);
+        self
+    }
+
+    /// Same as [`EntityList::with_max_entities`], named after [`EntityList::entry_limit`].
+    pub fn with_entry_limit(self, max: usize) -> Self {
+        self.with_max_entities(max)
+    }
+
+    /// Returns the limit of alive entities set by `with_max_entities` or `with_entry_limit`.
+    pub fn entry_limit(&self) -> Option<usize> {
+        self.1.max_entities
+    }
+
+    /// Compacts the list automatically in `remove` once there are more than `limit` dead slots.
+    ///
+    /// Since compaction changes the EntityIds of moved entities, register a callback with
+    /// `on_compact` to update the stored EntityIds.
+    pub fn with_tombstone_limit(mut self, limit: usize) -> Self {
+        self.1.tombstone_limit = Some(limit);
+        self
+    }
+
+    /// Registers a callback called with the (old, new) EntityIds of moved entities on every
+    /// compaction, replacing the previous one.
+    ///
+    /// The callback must be unwind safe so that the list stays `UnwindSafe` and
+    /// `RefUnwindSafe`. Share state through e.g. `Arc<Mutex<_>>`, or capture it in
+    /// `std::panic::AssertUnwindSafe`.
+    pub fn on_compact(
+        &mut self,
+        f: impl FnMut(&[(EntityId<I, G>, EntityId<I, G>)])
+            + Send
+            + Sync
+            + UnwindSafe
+            + RefUnwindSafe
+            + 'static,
+    ) {
+        self.1.on_compact = Some(Box::new(f));
+    }
+
+    pub fn len(&self) -> usize {
+        self.1.alive
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.1.alive == 0
+    }
+
+    /// Returns the number of slots in the backing Vec, including dead ones.
+    pub fn slot_count(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns all the slots including dead ones, for debugging tools and custom serializers.
+    ///
+    /// This is an introspection escape hatch and not part of the stable API.
+    #[doc(hidden)]
+    pub fn raw_slots(&self) -> &[EntityEntry<T, G>] {
+        &self.0
+    }
+
+    /// Mutable version of `raw_slots`. The slots cannot be made alive or dead through it.
+    #[doc(hidden)]
+    pub fn raw_slots_mut(&mut self) -> &mut [EntityEntry<T, G>] {
+        &mut self.0
+    }
+
+    /// Iterates the slots in chunks of `chunk_size` like `slice::chunks`, for batch processing
+    /// with predictable boundaries. Dead slots are included, so check
+    /// [`EntityEntry::value`] in each chunk.
+    ///
+    /// Panics if `chunk_size` is 0.
+    pub fn iter_chunks(
+        &self,
+        chunk_size: usize,
+    ) -> impl Iterator<Item = &[EntityEntry<T, G>]> + '_ {
+        self.0.chunks(chunk_size)
+    }
+
+    pub fn iter_chunks_mut(
+        &mut self,
+        chunk_size: usize,
+    ) -> impl Iterator<Item = &mut [EntityEntry<T, G>]> + '_ {
+        self.0.chunks_mut(chunk_size)
+    }
+
+    /// Returns two distinct slots at raw indices, alive or not, e.g. for a broad phase that only
+    /// knows slot indices. Returns None if either is out of bounds or they are the same.
+    ///
+    /// Check the liveness and generations with [`EntityEntry::value`] and
+    /// [`EntityEntry::generation`].
+    pub fn get_two_entries_mut(&mut self, a: usize, b: usize) -> Option<EntryPair<'_, T, G>> {
+        if a == b || self.0.len() <= a.max(b) {
+            return None;
+        }
+        if a < b {
+            let (left, right) = self.0.split_at_mut(b);
+            Some((&mut left[a], &mut right[0]))
+        } else {
+            let (left, right) = self.0.split_at_mut(a);
+            Some((&mut right[0], &mut left[b]))
+        }
+    }
+
+    /// Returns the number of dead slots that can be reused by `add`, which excludes the slots
+    /// whose generations are exhausted.
+    pub fn free_slot_count(&self) -> usize {
+        self.1.free.len()
+    }
+
+    /// Recounts the alive slots and rebuilds the free list from the slots, for the operations
+    /// that change many slots at once. The free list is ordered so that the lowest slot is reused
+    /// first.
+    fn rebuild_free_list(&mut self) {
+        self.1.alive = self.0.iter().filter(|entry| entry.entity.is_some()).count();
+        self.1.free = (0..self.0.len())
+            .rev()
+            .filter(|&i| self.0[i].reusable_gen().is_some())
+            .map(I::from_usize)
+            .collect();
+    }
+
+    /// Collects the raw slot indices satisfying `pred` with their bumped generations, leaving
+    /// out the slots whose generations cannot be bumped.
+    fn movable_slots(&self, pred: impl Fn(&EntityEntry<T, G>) -> bool) -> Vec<(usize, G)> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| pred(entry))
+            .filter_map(|(i, entry)| Some((i, entry.gen.checked_next_gen()?)))
+            .collect()
+    }
+
+    /// Collects the raw slot indices of alive slots.
+    fn alive_slots(&self) -> Vec<usize> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.entity.is_some())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Iterates the raw slot indices of dead slots.
+    pub fn iter_dead(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.entity.is_none())
+            .map(|(i, _)| i)
+    }
+
+    /// Returns the number of dead slots, which unlike [`EntityList::free_slot_count`] includes
+    /// the exhausted ones.
+    pub fn count_dead(&self) -> usize {
+        self.iter_dead().count()
+    }
+
+    /// Returns the number of slots that can be pushed without reallocating the backing Vec.
+    pub fn spare_capacity(&self) -> usize {
+        self.0.capacity() - self.0.len()
+    }
+
+    /// Returns the fraction of the slots that are alive, or 0 if there are no slots at all.
+    pub fn alive_fraction(&self) -> f32 {
+        self.len() as f32 / self.slot_count().max(1) as f32
+    }
+
+    /// Returns the fraction of the slots that are dead, i.e. `1 - alive_fraction`, which is 1 if
+    /// there are no slots at all.
+    ///
+    /// A ratio above 0.5 is a reasonable heuristic to call [`EntityList::compact`].
+    pub fn fragmentation_ratio(&self) -> f32 {
+        1. - self.alive_fraction()
+    }
+
+    /// The limit set by `with_max_entities`, capped by the number of slots `I` can index.
+    fn max_entities(&self) -> usize {
+        self.1
+            .max_entities
+            .map(|max| max.min(I::MAX_SLOTS))
+            .unwrap_or(I::MAX_SLOTS)
+    }
+
+    fn is_full(&self) -> bool {
+        self.max_entities() <= self.len()
+    }
+
+    /// Whether a slot can be pushed at the end, within the indices of `I` and with a generation
+    /// below `G::MAX`.
+    fn can_push_slot(&self) -> bool {
+        self.0.len() < I::MAX_SLOTS && self.1.gen_floor != G::MAX
+    }
+
+    /// Whether a new entity fits in a free slot or a pushed one, regardless of `max_entities`.
+    fn has_room(&self) -> bool {
+        self.can_push_slot() || !self.1.free.is_empty()
+    }
+
+    /// Panics if there is no slot left for the entity, or if it would exceed the limit set by
+    /// `with_max_entities` in debug builds or with the `strict-limits` feature.
+    pub fn add(&mut self, entity: T) -> EntityId<I, G> {
+        if cfg!(any(debug_assertions, feature = "strict-limits")) {
+            assert!(
+                !self.is_full(),
+                "EntityList exceeded the maximum number of entities {}",
+                self.max_entities()
+            );
+        }
+        self.add_unchecked(entity)
+    }
+
+    /// Adds an entity unless it would exceed the maximum number of entities, in which case the
+    /// entity is given back in `Err`.
+    pub fn try_add(&mut self, entity: T) -> Result<EntityId<I, G>, T> {
+        if self.is_full() || !self.has_room() {
+            return Err(entity);
+        }
+        Ok(self.add_unchecked(entity))
+    }
+
+    /// Adds an entity at the slot and generation of `preferred_id`, e.g. to restore a saved list
+    /// so that the stored EntityIds stay valid without remapping. Returns `Ok(preferred_id)` if
+    /// the entity was placed there, or otherwise adds it like `add` and returns the new EntityId
+    /// in `Err`.
+    ///
+    /// The slot must be empty and its generation must not have advanced past `preferred_id`'s.
+    /// A generation ahead of the slot's is accepted, since the slot has never issued it, so that
+    /// a save can be loaded into a fresh list whose slots all start at generation 0. Slots
+    /// beyond the end are created with dead slots in between. The slot index must be below the
+    /// limit set by `with_max_entities`, which also bounds the memory taken by the dead slots,
+    /// so set it when the EntityIds are untrusted.
+    ///
+    /// Panics in the same cases as `add`; see [`EntityList::try_add_stable`] to get the entity
+    /// back instead.
+    pub fn add_stable(
+        &mut self,
+        entity: T,
+        preferred_id: EntityId<I, G>,
+    ) -> Result<EntityId<I, G>, EntityId<I, G>> {
+        if self.stable_slot_available(preferred_id) {
+            Ok(self.place_stable(entity, preferred_id))
+        } else {
+            Err(self.add(entity))
+        }
+    }
+
+    /// Like `add_stable`, but falls back to `try_add`, so that the entity is given back in
+    /// [`AddStableError::Full`] if the list is full.
+    pub fn try_add_stable(
+        &mut self,
+        entity: T,
+        preferred_id: EntityId<I, G>,
+    ) -> Result<EntityId<I, G>, AddStableError<T, I, G>> {
+        if self.stable_slot_available(preferred_id) {
+            return Ok(self.place_stable(entity, preferred_id));
+        }
+        match self.try_add(entity) {
+            Ok(id) => Err(AddStableError::Relocated(id)),
+            Err(entity) => Err(AddStableError::Full(entity)),
+        }
+    }
+
+    fn stable_slot_available(&self, preferred_id: EntityId<I, G>) -> bool {
+        let index = preferred_id.id.to_usize();
+        let slot_gen = match self.0.get(index) {
+            Some(entry) if entry.entity.is_some() => None,
+            Some(entry) => Some(entry.gen),
+            None => Some(self.1.gen_floor),
+        };
+        !self.is_full()
+            && index < self.max_entities()
+            && preferred_id.gen != G::MAX
+            && slot_gen.is_some_and(|gen| gen <= preferred_id.gen)
+    }
+
+    fn place_stable(&mut self, entity: T, preferred_id: EntityId<I, G>) -> EntityId<I, G> {
+        let index = preferred_id.id.to_usize();
+        if self.0.len() <= index {
+            let gen = self.1.gen_floor;
+            let start = self.0.len();
+            self.0
+                .resize_with(index + 1, || EntityEntry { gen, entity: None });
+            if gen.checked_next_gen().is_some() {
+                self.1.free.extend((start..index).rev().map(I::from_usize));
+            }
+        } else {
+            // O(n) in the free slots, which is fine for loading
+            self.1.free.retain(|&i| i != preferred_id.id);
+        }
+        self.1.version += 1;
+        self.1.alive += 1;
+        self.0[index] = EntityEntry {
+            gen: preferred_id.gen,
+            entity: Some(entity),
+        };
+        #[cfg(feature = "observer")]
+        self.1.observers.notify_add(preferred_id, &self.0[index]);
+        preferred_id
+    }
+
+    fn add_unchecked(&mut self, entity: T) -> EntityId<I, G> {
+        let id = self.insert_into_free_slot(entity);
+        #[cfg(feature = "observer")]
+        self.1.observers.notify_add(id, &self.0[id.id.to_usize()]);
+        id
+    }
 
-impl EntityList {
-    fn add(&mut self, entity: Entity) -> EntityId {
-        for (i, entry) in self.0.iter_mut().enumerate() {
-            if entry.entity.is_none() {
+    fn insert_into_free_slot(&mut self, entity: T) -> EntityId<I, G> {
+        self.1.version += 1;
+        // The slots in the free list are reusable unless their generations were changed through
+        // `raw_slots_mut`.
+        while let Some(index) = self.1.free.pop() {
+            let entry = match self.0.get_mut(index.to_usize()) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            if let Some(gen) = entry.reusable_gen() {
                 entry.entity = Some(entity);
-                entry.gen += 1;
-                return EntityId {
-                    id: i as u32,
-                    gen: entry.gen,
-                };
+                entry.gen = gen;
+                self.1.alive += 1;
+                return EntityId { id: index, gen };
             }
         }
 
+        assert!(
+            self.can_push_slot(),
+            "EntityList: no slot left for a new entity"
+        );
+        self.1.alive += 1;
+        let gen = self.1.gen_floor;
         self.0.push(EntityEntry {
-            gen: 0,
+            gen,
             entity: Some(entity),
         });
         EntityId {
-            id: self.0.len() as u32 - 1,
-            gen: 0,
+            id: I::from_usize(self.0.len() - 1),
+            gen,
         }
     }
 
-    fn remove(&mut self, id: EntityId) -> Option<Entity> {
-        self.0
-            .get_mut(id.id as usize)
-            .and_then(|entry| entry.entity.take())
+    pub fn remove(&mut self, id: EntityId<I, G>) -> Option<T> {
+        self.try_remove(id).ok()
     }
 
-    fn get(&self, id: EntityId) -> Option<&Entity> {
-        self.0.get(id.id as usize).and_then(|e| {
+    /// Like `remove`, but tells why the entity could not be removed.
+    pub fn try_remove(&mut self, id: EntityId<I, G>) -> Result<T, RemoveError> {
+        let entity = self.take(id)?;
+        self.compact_over_tombstone_limit();
+        Ok(entity)
+    }
+
+    /// Removes an entity without compaction.
+    fn take(&mut self, id: EntityId<I, G>) -> Result<T, RemoveError> {
+        let entry = self
+            .0
+            .get_mut(id.id.to_usize())
+            .ok_or(RemoveError::SlotOutOfBounds)?;
+        if entry.gen != id.gen {
+            return Err(RemoveError::GenerationMismatch {
+                current_gen: entry.gen.into(),
+            });
+        }
+        #[cfg(feature = "observer")]
+        self.1.observers.notify_remove(id, entry);
+        let entity = entry.entity.take().ok_or(RemoveError::SlotAlreadyEmpty)?;
+        if entry.reusable_gen().is_some() {
+            self.1.free.push(id.id);
+        }
+        self.1.alive -= 1;
+        #[cfg(feature = "dirty-tracking")]
+        if let Some(dirty) = self.1.dirty.get_mut(id.id.to_usize()) {
+            *dirty = false;
+        }
+        self.1.version += 1;
+        Ok(entity)
+    }
+
+    /// Truncates the slots to `raw_index`, raising `gen_floor` above the generations of the
+    /// dropped slots. If any of them cannot be bumped, `gen_floor` becomes `G::MAX` and no slot
+    /// can be pushed any more.
+    fn drop_slots_from(&mut self, raw_index: usize) -> Vec<EntityEntry<T, G>> {
+        let dropped = self.0.split_off(raw_index);
+        for entry in &dropped {
+            let floor = entry.gen.checked_next_gen().unwrap_or(G::MAX);
+            self.1.gen_floor = self.1.gen_floor.max(floor);
+            if entry.entity.is_some() {
+                self.1.alive -= 1;
+            }
+        }
+        self.1.free.retain(|i| i.to_usize() < raw_index);
+        #[cfg(feature = "dirty-tracking")]
+        self.1.dirty.truncate(raw_index);
+        dropped
+    }
+
+    /// Drops the dead slots at the end, but keeps the exhausted ones, whose generations cannot
+    /// be carried over.
+    fn drop_dead_tail(&mut self) {
+        let end = self
+            .0
+            .iter()
+            .rposition(|entry| entry.reusable_gen().is_none())
+            .map_or(0, |i| i + 1);
+        if end < self.0.len() {
+            self.drop_slots_from(end);
+        }
+    }
+
+    fn compact_over_tombstone_limit(&mut self) {
+        let limit = match self.1.tombstone_limit {
+            Some(limit) => limit,
+            None => return,
+        };
+        if self.free_slot_count() <= limit {
+            return;
+        }
+        #[cfg(debug_assertions)]
+        if self.1.on_compact.is_none() {
+            eprintln!("warning: EntityList compacted without on_compact callback, EntityIds of moved entities are stale");
+        }
+        self.compact();
+    }
+
+    /// Moves alive entities into the dead slots before them, keeping the order, and drops the
+    /// dead slots at the end. Exhausted slots are skipped, since they cannot take another entity.
+    ///
+    /// Moved entities get new EntityIds with bumped generations. Returns the (old, new)
+    /// EntityIds of them, which are also passed to the `on_compact` callback.
+    ///
+    /// The generations of the dropped slots are remembered, so that slots pushed later start
+    /// above them and stale EntityIds beyond the new end never alias new entities.
+    pub fn compact(&mut self) -> Vec<(EntityId<I, G>, EntityId<I, G>)> {
+        self.1.version += 1;
+        let mut remap = vec![];
+        let mut dest = 0;
+        for src in 0..self.0.len() {
+            if self.0[src].entity.is_none() {
+                continue;
+            }
+            while dest < src && self.0[dest].reusable_gen().is_none() {
+                dest += 1;
+            }
+            if src != dest {
+                let old_id = EntityId {
+                    id: I::from_usize(src),
+                    gen: self.0[src].gen,
+                };
+                let entity = self.0[src].entity.take();
+                #[cfg(feature = "dirty-tracking")]
+                self.move_dirty(src, dest);
+                let entry = &mut self.0[dest];
+                entry.gen = entry
+                    .reusable_gen()
+                    .expect("compact: dest should be reusable");
+                entry.entity = entity;
+                remap.push((
+                    old_id,
+                    EntityId {
+                        id: I::from_usize(dest),
+                        gen: entry.gen,
+                    },
+                ));
+            }
+            dest += 1;
+        }
+        self.drop_dead_tail();
+        self.rebuild_free_list();
+        if let Some(f) = &mut self.1.on_compact {
+            f(&remap);
+        }
+        remap
+    }
+
+    /// Removes an entity and moves the entity in the highest slot into the vacated slot, so that
+    /// alive entities stay packed at the front. The dead slots left at the end are dropped, and
+    /// their generations carry over to the slots pushed later like in `compact`.
+    ///
+    /// The moved entity gets a new EntityId with the bumped generation of the vacated slot, which
+    /// is returned as `Some((old_id, new_id))`. The caller must update any stored references to
+    /// it. If the removed entity was in the highest slot or its slot is exhausted, nothing is
+    /// moved.
+    pub fn swap_remove_alive(&mut self, id: EntityId<I, G>) -> Option<(T, Option<MovedId<I, G>>)> {
+        let entity = self.take(id).ok()?;
+        let moved = self.swap_into_vacated(id);
+        self.drop_dead_tail();
+        Some((entity, moved))
+    }
+
+    /// Moves the entity in the highest slot into the slot vacated by `id`, if it is above it.
+    fn swap_into_vacated(&mut self, id: EntityId<I, G>) -> Option<MovedId<I, G>> {
+        let gen = self.0[id.id.to_usize()].reusable_gen()?;
+        let last = self.last_alive().filter(|last| id.id < last.id)?;
+        // The vacated slot has just been pushed to the free list by `take`.
+        self.1.free.pop();
+        if self.0[last.id.to_usize()].reusable_gen().is_some() {
+            self.1.free.push(last.id);
+        }
+        let moved = self.0[last.id.to_usize()].entity.take();
+        #[cfg(feature = "dirty-tracking")]
+        self.move_dirty(last.id.to_usize(), id.id.to_usize());
+        let entry = &mut self.0[id.id.to_usize()];
+        entry.gen = gen;
+        entry.entity = moved;
+        Some((last, EntityId { id: id.id, gen }))
+    }
+
+    pub fn get(&self, id: EntityId<I, G>) -> Option<&T> {
+        self.0.get(id.id.to_usize()).and_then(|e| {
             if e.gen == id.gen {
                 e.entity.as_ref()
             } else {
@@ -71,8 +1000,8 @@ impl EntityList {
         })
     }
 
-    fn get_mut(&mut self, id: EntityId) -> Option<&mut Entity> {
-        self.0.get_mut(id.id as usize).and_then(|e| {
+    pub fn get_mut(&mut self, id: EntityId<I, G>) -> Option<&mut T> {
+        self.0.get_mut(id.id.to_usize()).and_then(|e| {
             if e.gen == id.gen {
                 e.entity.as_mut()
             } else {
@@ -81,65 +1010,892 @@ impl EntityList {
         })
     }
 
-    fn get_pair_mut(
+    /// Returns the raw slot index of the entity if it is alive, e.g. to look up a parallel
+    /// array indexed by slots.
+    pub fn position(&self, id: EntityId<I, G>) -> Option<usize> {
+        self.get(id)?;
+        Some(id.id.to_usize())
+    }
+
+    /// Returns whether any entity is alive at the raw slot index, whatever its generation.
+    ///
+    /// This bypasses the generation check that protects EntityIds from aliasing a new entity in
+    /// a reused slot, so it is only for external indices that track staleness by themselves.
+    pub fn alive_at(&self, raw_index: usize) -> bool {
+        self.0
+            .get(raw_index)
+            .is_some_and(|entry| entry.entity.is_some())
+    }
+
+    /// Returns the EntityId of the entity currently alive at the raw slot index, which is the
+    /// inverse of [`EntityList::position`]. It lets a caller that stored only raw indices
+    /// re-validate them.
+    pub fn get_current_id(&self, raw_index: I) -> Option<EntityId<I, G>> {
+        let entry = self.0.get(raw_index.to_usize())?;
+        entry.entity.as_ref()?;
+        Some(EntityId {
+            id: raw_index,
+            gen: entry.gen,
+        })
+    }
+
+    /// Returns the entities for a fixed number of EntityIds. Unlike mutable access, the same
+    /// EntityId may appear more than once.
+    pub fn get_many<const N: usize>(&self, ids: [EntityId<I, G>; N]) -> [Option<&T>; N] {
+        ids.map(|id| self.get(id))
+    }
+
+    /// Runtime-length version of [`EntityList::get_many`].
+    pub fn get_many_slice(&self, ids: &[EntityId<I, G>]) -> Vec<Option<&T>> {
+        ids.iter().map(|id| self.get(*id)).collect()
+    }
+
+    /// Immutable counterpart of [`EntityList::get_pair_mut`].
+    pub fn get_pair_ref(&self, a: EntityId<I, G>, b: EntityId<I, G>) -> (Option<&T>, Option<&T>) {
+        (self.get(a), self.get(b))
+    }
+
+    pub fn get_pair_mut(
         &mut self,
-        a: EntityId,
-        b: EntityId,
-    ) -> (Option<&mut Entity>, Option<&mut Entity>) {
+        a: EntityId<I, G>,
+        b: EntityId<I, G>,
+    ) -> (Option<&mut T>, Option<&mut T>) {
         if a.id < b.id {
-            let (left, right) = self.0.split_at_mut(b.id as usize);
+            let (left, right) = self.0.split_at_mut(b.id.to_usize());
             (
-                left.get_mut(a.id as usize)
+                left.get_mut(a.id.to_usize())
                     .and_then(|entry| entry.get_mut(a)),
                 right.first_mut().and_then(|s| s.get_mut(b)),
             )
         } else if b.id < a.id {
-            let (left, right) = self.0.split_at_mut(a.id as usize);
+            let (left, right) = self.0.split_at_mut(a.id.to_usize());
             (
                 right.first_mut().and_then(|s| s.get_mut(a)),
-                left.get_mut(b.id as usize)
+                left.get_mut(b.id.to_usize())
                     .and_then(|entry| entry.get_mut(b)),
             )
             // The following cases are when a and b points to the same index. In that case we want to return
             // only the one with valid generation.
         } else if self
             .0
-            .get(a.id as usize)
+            .get(a.id.to_usize())
             .map(|a_obj| a.gen != a_obj.gen)
             .unwrap_or(true)
         {
             (
                 None,
                 self.0
-                    .get_mut(b.id as usize)
+                    .get_mut(b.id.to_usize())
                     .and_then(|entry| entry.get_mut(b)),
             )
         } else {
             (
                 self.0
-                    .get_mut(a.id as usize)
+                    .get_mut(a.id.to_usize())
                     .and_then(|entry| entry.get_mut(a)),
                 None,
             )
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::{Entity, EntityId, EntityList};
+    /// Three-entity version of [`EntityList::get_pair_mut`], e.g. for the vertices of a
+    /// triangle.
+    ///
+    /// An entity is returned only once: if EntityIds point to the same slot, the first alive one
+    /// gets it and the others get None.
+    pub fn get_three_mut(
+        &mut self,
+        a: EntityId<I, G>,
+        b: EntityId<I, G>,
+        c: EntityId<I, G>,
+    ) -> (Option<&mut T>, Option<&mut T>, Option<&mut T>) {
+        let ids = [a, b, c];
+        let mut slots = [None; 3];
+        for k in 0..3 {
+            let slot = ids[k].id.to_usize();
+            if self.get(ids[k]).is_some() && !slots[..k].contains(&Some(slot)) {
+                slots[k] = Some(slot);
+            }
+        }
 
-    #[test]
-    fn it_works() {
-        let mut el = EntityList::default();
-        let a = el.add(Entity { name: "a" });
-        let b = el.add(Entity { name: "b" });
-        let c = el.add(Entity { name: "c" });
-        assert_eq!(b, EntityId { id: 1, gen: 0 });
-        el.remove(b);
-        assert_eq!(el.get(a), Some(&Entity { name: "a" }));
-        assert_eq!(el.get(b), None);
-        assert_eq!(el.get(c), Some(&Entity { name: "c" }));
+        // Sort the arguments by slot with a comparison network, putting the missing ones last, so
+        // that at most two splits separate the slots.
+        let key = |k: usize| slots[k].unwrap_or(usize::MAX);
+        let mut order = [0, 1, 2];
+        for &(i, j) in &[(0, 1), (1, 2), (0, 1)] {
+            if key(order[j]) < key(order[i]) {
+                order.swap(i, j);
+            }
+        }
+
+        let [first, second, third] = order;
+        let mut ret = [None, None, None];
+        match (slots[first], slots[second], slots[third]) {
+            (Some(x), Some(y), Some(z)) => {
+                let (left, rest) = self.0.split_at_mut(y);
+                let (middle, right) = rest.split_at_mut(z - y);
+                ret[first] = left[x].entity.as_mut();
+                ret[second] = middle[0].entity.as_mut();
+                ret[third] = right[0].entity.as_mut();
+            }
+            (Some(x), Some(y), None) => {
+                let (left, right) = self.0.split_at_mut(y);
+                ret[first] = left[x].entity.as_mut();
+                ret[second] = right[0].entity.as_mut();
+            }
+            (Some(x), None, None) => ret[first] = self.0[x].entity.as_mut(),
+            _ => {}
+        }
+        let [a, b, c] = ret;
+        (a, b, c)
+    }
+
+    /// Like [`EntityList::get_pair_mut`], but panics if either EntityId is stale or both point to
+    /// the same entity, for code where that is a bug.
+    #[track_caller]
+    pub fn get_two_mut(&mut self, a: EntityId<I, G>, b: EntityId<I, G>) -> (&mut T, &mut T) {
+        assert!(
+            a.id != b.id,
+            "EntityList: get_two_mut with the same slot {}",
+            a
+        );
+        match self.get_pair_mut(a, b) {
+            (Some(a), Some(b)) => (a, b),
+            (None, _) => panic!("EntityList: no entity with id {}", a),
+            (_, None) => panic!("EntityList: no entity with id {}", b),
+        }
+    }
+
+    /// Sorts alive entities with the comparator `cmp` and packs them to the front of the
+    /// backing Vec, leaving dead slots at the end.
+    ///
+    /// Entities that were moved to another slot get a new EntityId with a bumped generation.
+    /// Returns the list of (old, new) EntityIds of moved entities, so that the caller can fix up
+    /// any references to them.
+    ///
+    /// Slots whose generations cannot be bumped any more are left out, i.e. their entities stay
+    /// in place and no entity is moved into them.
+    pub fn sort_unstable_by<F>(&mut self, mut cmp: F) -> Vec<(EntityId<I, G>, EntityId<I, G>)>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.1.version += 1;
+        let slots = self.movable_slots(|_| true);
+        let mut alive: Vec<_> = slots
+            .iter()
+            .filter_map(|&(i, _)| {
+                let entry = &mut self.0[i];
+                let id = EntityId {
+                    id: I::from_usize(i),
+                    gen: entry.gen,
+                };
+                Some((id, entry.entity.take()?))
+            })
+            .collect();
+        alive.sort_unstable_by(|a, b| cmp(&a.1, &b.1));
+
+        #[cfg(feature = "dirty-tracking")]
+        {
+            let dirty = &mut self.1.dirty;
+            let flags: Vec<_> = alive
+                .iter()
+                .map(|(id, _)| dirty.get(id.id.to_usize()) == Some(&true))
+                .collect();
+            for &(i, _) in &slots {
+                if let Some(flag) = dirty.get_mut(i) {
+                    *flag = false;
+                }
+            }
+            for (&(i, _), flag) in slots.iter().zip(flags) {
+                if flag {
+                    dirty.resize(dirty.len().max(i + 1), false);
+                    dirty[i] = true;
+                }
+            }
+        }
+
+        let mut remap = vec![];
+        for (&(i, gen), (old_id, entity)) in slots.iter().zip(alive) {
+            let entry = &mut self.0[i];
+            if old_id.id.to_usize() != i {
+                entry.gen = gen;
+                remap.push((
+                    old_id,
+                    EntityId {
+                        id: I::from_usize(i),
+                        gen: entry.gen,
+                    },
+                ));
+            }
+            entry.entity = Some(entity);
+        }
+        self.rebuild_free_list();
+        debug_assert_eq!(self.validate(), Ok(()));
+        remap
+    }
+
+    /// Rotates the alive entities left by `n` among the alive slots, so that the entity in the
+    /// k-th alive slot moves to the (k - n)-th one, wrapping around. Dead slots are untouched.
+    ///
+    /// Like `sort_unstable_by`, moved entities get new EntityIds with bumped generations, which
+    /// are returned as (old, new) pairs, and entities in slots whose generations cannot be bumped
+    /// any more stay in place.
+    pub fn rotate_alive_left(&mut self, n: usize) -> Vec<MovedId<I, G>> {
+        self.1.version += 1;
+        let slots = self.movable_slots(|entry| entry.entity.is_some());
+        let shift = match slots.len() {
+            0 => return vec![],
+            len => n % len,
+        };
+        if shift == 0 {
+            return vec![];
+        }
+
+        let mut moving: Vec<_> = slots
+            .iter()
+            .filter_map(|&(i, _)| {
+                let entry = &mut self.0[i];
+                let id = EntityId {
+                    id: I::from_usize(i),
+                    gen: entry.gen,
+                };
+                Some((id, entry.entity.take()?))
+            })
+            .collect();
+        moving.rotate_left(shift);
+
+        #[cfg(feature = "dirty-tracking")]
+        {
+            let dirty = &mut self.1.dirty;
+            let mut flags: Vec<_> = slots
+                .iter()
+                .map(|&(i, _)| dirty.get(i) == Some(&true))
+                .collect();
+            flags.rotate_left(shift);
+            dirty.resize(dirty.len().max(slots[slots.len() - 1].0 + 1), false);
+            for (&(i, _), flag) in slots.iter().zip(flags) {
+                dirty[i] = flag;
+            }
+        }
+
+        let mut remap = Vec::with_capacity(slots.len());
+        for (&(i, gen), (old_id, entity)) in slots.iter().zip(moving) {
+            let entry = &mut self.0[i];
+            entry.gen = gen;
+            entry.entity = Some(entity);
+            let new_id = EntityId {
+                id: I::from_usize(i),
+                gen: entry.gen,
+            };
+            remap.push((old_id, new_id));
+        }
+        remap
+    }
+
+    /// Removes each alive entity for which `same(entity, previous)` returns true, where
+    /// `previous` is the last retained alive entity before it in slot order, like
+    /// `Vec::dedup_by`. Sort the list first to remove all the duplicates.
+    ///
+    /// Like `remove`, the generations of the vacated slots are bumped when they are reused, so
+    /// the removed EntityIds never point to another entity. Returns them, e.g. to clean up
+    /// references. Also like `remove`, the list may be compacted at the end if a tombstone limit
+    /// is set.
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(
+        &mut self,
+        mut same: F,
+    ) -> Vec<EntityId<I, G>> {
+        let mut removed = vec![];
+        let mut prev: Option<usize> = None;
+        for i in 0..self.0.len() {
+            if self.0[i].entity.is_none() {
+                continue;
+            }
+            let p = match prev {
+                Some(p) => p,
+                None => {
+                    prev = Some(i);
+                    continue;
+                }
+            };
+            let (left, right) = self.0.split_at_mut(i);
+            let is_same = match (right[0].entity.as_mut(), left[p].entity.as_mut()) {
+                (Some(entity), Some(previous)) => same(entity, previous),
+                _ => false,
+            };
+            if !is_same {
+                prev = Some(i);
+                continue;
+            }
+            let id = EntityId {
+                id: I::from_usize(i),
+                gen: self.0[i].gen,
+            };
+            if self.take(id).is_ok() {
+                removed.push(id);
+            }
+        }
+        self.compact_over_tombstone_limit();
+        removed
+    }
+
+    /// Same as `dedup_by`, comparing the keys given by `key`.
+    pub fn dedup_by_key<K: Eq, F: Fn(&T) -> K>(&mut self, key: F) -> Vec<EntityId<I, G>> {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    /// Iterates alive entities with their EntityIds in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId<I, G>, &T)> {
+        self.0.iter().enumerate().filter_map(|(i, entry)| {
+            Some((
+                EntityId {
+                    id: I::from_usize(i),
+                    gen: entry.gen,
+                },
+                entry.entity.as_ref()?,
+            ))
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (EntityId<I, G>, &mut T)> {
+        self.0.iter_mut().enumerate().filter_map(|(i, entry)| {
+            Some((
+                EntityId {
+                    id: I::from_usize(i),
+                    gen: entry.gen,
+                },
+                entry.entity.as_mut()?,
+            ))
+        })
+    }
+
+    /// Iterates alive entities with a dense index counting only alive ones, e.g. to index a
+    /// packed parallel array. The index is only meaningful until the list is modified.
+    pub fn enumerate_alive(&self) -> impl Iterator<Item = (usize, EntityId<I, G>, &T)> + '_ {
+        self.iter()
+            .enumerate()
+            .map(|(i, (id, entity))| (i, id, entity))
+    }
+
+    pub fn enumerate_alive_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (usize, EntityId<I, G>, &mut T)> + '_ {
+        self.iter_mut()
+            .enumerate()
+            .map(|(i, (id, entity))| (i, id, entity))
+    }
+
+    /// Counts alive entities in the range of raw slot indices, e.g. for a region occupying
+    /// contiguous slots. It scans the range.
+    ///
+    /// Panics if the range is out of the slots, like slicing.
+    pub fn count_in_range(&self, range: impl RangeBounds<usize>) -> usize {
+        self.0[(range.start_bound().cloned(), range.end_bound().cloned())]
+            .iter()
+            .filter(|entry| entry.entity.is_some())
+            .count()
+    }
+
+    /// Iterates alive entities in the range of raw slot indices with their EntityIds.
+    ///
+    /// Panics if the range is out of the slots, like slicing.
+    pub fn iter_range(
+        &self,
+        range: impl RangeBounds<usize>,
+    ) -> impl Iterator<Item = (EntityId<I, G>, &T)> + '_ {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        self.0[(range.start_bound().cloned(), range.end_bound().cloned())]
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, entry)| {
+                Some((
+                    EntityId {
+                        id: I::from_usize(start + i),
+                        gen: entry.gen,
+                    },
+                    entry.entity.as_ref()?,
+                ))
+            })
+    }
+
+    /// Iterates alive entities from the slot of `start`, so that an iteration can be resumed
+    /// later, e.g. in the next frame. It does not wrap around.
+    pub fn iter_from(&self, start: EntityId<I, G>) -> impl Iterator<Item = (EntityId<I, G>, &T)> {
+        self.0
+            .iter()
+            .enumerate()
+            .skip(start.id.to_usize())
+            .filter_map(|(i, entry)| {
+                Some((
+                    EntityId {
+                        id: I::from_usize(i),
+                        gen: entry.gen,
+                    },
+                    entry.entity.as_ref()?,
+                ))
+            })
+    }
+
+    /// Iterates the EntityIds of alive entities in slot order.
+    pub fn ids(&self) -> impl Iterator<Item = EntityId<I, G>> + '_ {
+        self.iter().map(|(id, _)| id)
+    }
+
+    /// Iterates EntityIds of alive entities as `(prev, current, next)`, where `prev` and `next`
+    /// are the adjacent alive entities in slot order.
+    pub fn iter_with_neighbors(&self) -> impl Iterator<Item = Neighbors<I, G>> + '_ {
+        let mut ids = self.ids().peekable();
+        let mut prev = None;
+        std::iter::from_fn(move || {
+            let current = ids.next()?;
+            let ret = (prev, current, ids.peek().copied());
+            prev = Some(current);
+            Some(ret)
+        })
+    }
+
+    /// Converts every alive entity with `f`, keeping the slots and generations, so that the
+    /// EntityIds stay valid in the new list.
+    ///
+    /// The settings and the `on_compact` callback are carried over, but the observers are not
+    /// since they take the old entity type.
+    pub fn map<U>(self, f: impl Fn(EntityId<I, G>, T) -> U) -> EntityListImpl<U, I, G> {
+        let slots = self
+            .0
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let id = EntityId {
+                    id: I::from_usize(i),
+                    gen: entry.gen,
+                };
+                EntityEntry {
+                    gen: entry.gen,
+                    entity: entry.entity.map(|entity| f(id, entity)),
+                }
+            })
+            .collect();
+        EntityListImpl(slots, self.1.with_entity_type())
+    }
+
+    /// Like [`EntityList::map`], but removes the entities for which `f` returns None, leaving
+    /// their slots dead like `remove` does, while the other entities keep their EntityIds.
+    pub fn filter_map<U>(
+        self,
+        f: impl Fn(EntityId<I, G>, T) -> Option<U>,
+    ) -> EntityListImpl<U, I, G> {
+        let mut config = self.1.with_entity_type();
+        config.version += 1;
+        let slots = self
+            .0
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let id = EntityId {
+                    id: I::from_usize(i),
+                    gen: entry.gen,
+                };
+                let entity = match entry.entity {
+                    Some(entity) => entity,
+                    None => {
+                        return EntityEntry {
+                            gen: entry.gen,
+                            entity: None,
+                        }
+                    }
+                };
+                match f(id, entity) {
+                    Some(entity) => EntityEntry {
+                        gen: entry.gen,
+                        entity: Some(entity),
+                    },
+                    None => {
+                        #[cfg(feature = "dirty-tracking")]
+                        if let Some(dirty) = config.dirty.get_mut(i) {
+                            *dirty = false;
+                        }
+                        EntityEntry {
+                            gen: entry.gen,
+                            entity: None,
+                        }
+                    }
+                }
+            })
+            .collect();
+        let mut list = EntityListImpl(slots, config);
+        list.rebuild_free_list();
+        list
+    }
+
+    /// Splits the list in two at the slot `raw_index`, like `Vec::split_off`. `self` keeps the
+    /// slots before it and the returned list gets the rest with the same generations.
+    ///
+    /// The returned list starts with `raw_index` dead slots, so that the EntityIds in both lists
+    /// stay valid, and `add` may fill them. The settings are copied, but the callbacks are not.
+    ///
+    /// Panics if `raw_index` is greater than the number of slots.
+    pub fn split_off_at(&mut self, raw_index: usize) -> EntityListImpl<T, I, G> {
+        self.1.version += 1;
+        let config = ListConfig {
+            #[cfg(feature = "dirty-tracking")]
+            dirty: self.split_off_dirty(raw_index),
+            ..self.1.clone()
+        };
+        let tail = self.drop_slots_from(raw_index);
+        let mut slots = Vec::with_capacity(raw_index + tail.len());
+        slots.resize_with(raw_index, || EntityEntry {
+            gen: G::default(),
+            entity: None,
+        });
+        slots.extend(tail);
+        let mut list = EntityListImpl(slots, config);
+        list.rebuild_free_list();
+        list
+    }
+
+    /// Consumes the list into the alive entities with their EntityIds in slot order.
+    pub fn into_vec_with_ids(self) -> Vec<(EntityId<I, G>, T)> {
+        let mut ret = Vec::with_capacity(self.len());
+        ret.extend(self.0.into_iter().enumerate().filter_map(|(i, entry)| {
+            Some((
+                EntityId {
+                    id: I::from_usize(i),
+                    gen: entry.gen,
+                },
+                entry.entity?,
+            ))
+        }));
+        ret
+    }
+
+    /// Consumes the list into a HashMap keyed by the EntityIds of alive entities.
+    pub fn into_hashmap(self) -> HashMap<EntityId<I, G>, T> {
+        self.into_vec_with_ids().into_iter().collect()
+    }
+
+    /// Rebuilds a list from the EntityIds and entities, e.g. from
+    /// [`EntityList::into_hashmap`]. Each entity is placed at the slot of its EntityId with the
+    /// same generation, and the slots in between are dead with generation 0.
+    ///
+    /// Fails on an EntityId with the generation `G::MAX`, which a list never issues. Since the
+    /// slots up to the highest index are allocated, an index near `u32::MAX` takes that many
+    /// slots in an [`EntityList`], so use [`EntityList::from_hashmap_with_max_entities`] for
+    /// untrusted maps.
+    pub fn from_hashmap(map: HashMap<EntityId<I, G>, T>) -> Result<Self, FromHashMapError<I, G>> {
+        Self::default().fill_from_hashmap(map)
+    }
+
+    /// Like `from_hashmap`, but sets the limit of alive entities like `with_max_entities` and
+    /// fails on an index at or beyond it, which bounds the slots.
+    pub fn from_hashmap_with_max_entities(
+        map: HashMap<EntityId<I, G>, T>,
+        max: usize,
+    ) -> Result<Self, FromHashMapError<I, G>> {
+        Self::default()
+            .with_max_entities(max)
+            .fill_from_hashmap(map)
+    }
+
+    fn fill_from_hashmap(
+        mut self,
+        map: HashMap<EntityId<I, G>, T>,
+    ) -> Result<Self, FromHashMapError<I, G>> {
+        let max = self.max_entities();
+        for &id in map.keys() {
+            if max <= id.id.to_usize() {
+                return Err(FromHashMapError::IndexOutOfRange(id));
+            }
+            if id.gen == G::MAX {
+                return Err(FromHashMapError::ExhaustedGeneration(id));
+            }
+        }
+        let slot_count = map.keys().map(|id| id.id.to_usize() + 1).max().unwrap_or(0);
+        self.0.resize_with(slot_count, || EntityEntry {
+            gen: G::default(),
+            entity: None,
+        });
+        for (id, entity) in map {
+            self.0[id.id.to_usize()] = EntityEntry {
+                gen: id.gen,
+                entity: Some(entity),
+            };
+        }
+        self.rebuild_free_list();
+        Ok(self)
+    }
+
+    /// Consumes the list into the alive entities in slot order.
+    pub fn into_values(self) -> Vec<T> {
+        let mut ret = Vec::with_capacity(self.len());
+        ret.extend(self.0.into_iter().filter_map(|entry| entry.entity));
+        ret
+    }
+
+    /// Iterates overlapping windows of `n` consecutive alive entities in slot order, skipping
+    /// dead slots. Like `slice::windows`, nothing is yielded if there are fewer than `n`.
+    ///
+    /// Panics if `n` is 0.
+    pub fn windows_alive(&self, n: usize) -> impl Iterator<Item = Vec<(EntityId<I, G>, &T)>> + '_ {
+        assert!(n != 0, "EntityList: window size must be non-zero");
+        let mut iter = self.iter();
+        let mut window = VecDeque::with_capacity(n);
+        std::iter::from_fn(move || {
+            if window.len() == n {
+                window.pop_front();
+            }
+            while window.len() < n {
+                window.push_back(iter.next()?);
+            }
+            Some(window.iter().copied().collect())
+        })
+    }
+
+    /// Calls `f` with each overlapping window of `n` consecutive alive entities in slot order,
+    /// like [`EntityList::windows_alive`] with mutable access, e.g. to update each link of a
+    /// chain from its neighbors.
+    ///
+    /// It takes a callback rather than returning an iterator, since an iterator cannot yield
+    /// overlapping mutable windows that outlive each other.
+    ///
+    /// Panics if `n` is 0.
+    pub fn iter_alive_windows_mut(
+        &mut self,
+        n: usize,
+        mut f: impl FnMut(&mut [(EntityId<I, G>, &mut T)]),
+    ) {
+        assert!(n != 0, "EntityList: window size must be non-zero");
+        let alive = self.alive_slots();
+        for start in 0..(alive.len() + 1).saturating_sub(n) {
+            // Borrow each slot in the window from the remainder after the previous one, so that
+            // split_at_mut guarantees the references are disjoint.
+            let mut window = Vec::with_capacity(n);
+            let mut rest = &mut self.0[..];
+            let mut offset = 0;
+            for &slot in &alive[start..start + n] {
+                let (head, tail) = std::mem::take(&mut rest).split_at_mut(slot - offset + 1);
+                let entry = &mut head[slot - offset];
+                let id = EntityId {
+                    id: I::from_usize(slot),
+                    gen: entry.gen,
+                };
+                if let Some(entity) = entry.entity.as_mut() {
+                    window.push((id, entity));
+                }
+                rest = tail;
+                offset = slot + 1;
+            }
+            f(&mut window);
+        }
+    }
+
+    /// Collects the EntityIds of alive entities, so that the snapshot can be iterated while
+    /// mutating the list.
+    pub fn alive_ids_compact(&self) -> Vec<EntityId<I, G>> {
+        let mut ret = Vec::with_capacity(self.len());
+        ret.extend(self.ids());
+        ret
+    }
+
+    /// `Iterator::scan` over alive entities with their EntityIds. The iteration stops when `f`
+    /// returns None.
+    pub fn scan<'a, S, F>(&'a self, init: S, mut f: F) -> impl Iterator<Item = S> + 'a
+    where
+        S: 'a,
+        F: FnMut(&mut S, EntityId<I, G>, &T) -> Option<S> + 'a,
+    {
+        self.iter()
+            .scan(init, move |state, (id, entity)| f(state, id, entity))
+    }
+
+    /// `Iterator::fold` over alive entities with their EntityIds.
+    pub fn fold_ids<B>(&self, init: B, mut f: impl FnMut(B, EntityId<I, G>, &T) -> B) -> B {
+        self.iter()
+            .fold(init, |acc, (id, entity)| f(acc, id, entity))
+    }
+
+    pub fn fold_ids_mut<B>(
+        &mut self,
+        init: B,
+        mut f: impl FnMut(B, EntityId<I, G>, &mut T) -> B,
+    ) -> B {
+        self.iter_mut()
+            .fold(init, |acc, (id, entity)| f(acc, id, entity))
+    }
+
+    /// Iterates the slots where both `self` and `other` have alive entities.
+    ///
+    /// It is meant for parallel lists of different components sharing the same ID space, i.e.
+    /// the entities are always added to and removed from both lists at once. The generations are
+    /// not compared and the EntityId is the one of `self`.
+    pub fn zip_with<'a, U>(
+        &'a self,
+        other: &'a EntityListImpl<U, I, G>,
+    ) -> impl Iterator<Item = (EntityId<I, G>, &'a T, &'a U)> + 'a {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .enumerate()
+            .filter_map(|(i, (entry, other_entry))| {
+                Some((
+                    EntityId {
+                        id: I::from_usize(i),
+                        gen: entry.gen,
+                    },
+                    entry.entity.as_ref()?,
+                    other_entry.entity.as_ref()?,
+                ))
+            })
+    }
+
+    /// Iterates alive entities of `self` and then of `other`, e.g. to process several regions
+    /// as one sequence.
+    ///
+    /// The EntityIds are yielded verbatim, so an EntityId from one list must not be used with
+    /// the other.
+    pub fn chain_iter<'a>(
+        &'a self,
+        other: &'a EntityListImpl<T, I, G>,
+    ) -> impl Iterator<Item = (EntityId<I, G>, &'a T)> + 'a {
+        self.iter().chain(other.iter())
+    }
+
+    /// Calls `f` on each alive entity in slot order, keeping the entity and collecting the
+    /// result if it returns Some, or removing the entity if it returns None.
+    ///
+    /// Like `remove`, the list may be compacted at the end if a tombstone limit is set.
+    pub fn retain_collect<U>(
+        &mut self,
+        mut f: impl FnMut(EntityId<I, G>, &mut T) -> Option<U>,
+    ) -> Vec<U> {
+        let mut ret = vec![];
+        for i in 0..self.0.len() {
+            let entry = &mut self.0[i];
+            let id = EntityId {
+                id: I::from_usize(i),
+                gen: entry.gen,
+            };
+            let entity = match entry.entity.as_mut() {
+                Some(entity) => entity,
+                None => continue,
+            };
+            match f(id, entity) {
+                Some(value) => ret.push(value),
+                None => {
+                    let _ = self.take(id);
+                }
+            }
+        }
+        self.compact_over_tombstone_limit();
+        ret
+    }
+
+    /// Calls `f` on each alive entity in `ids` in the given order, skipping stale ones.
+    pub fn apply_to_many(
+        &mut self,
+        ids: &[EntityId<I, G>],
+        mut f: impl FnMut(EntityId<I, G>, &mut T),
+    ) {
+        for &id in ids {
+            if let Some(entity) = self.get_mut(id) {
+                f(id, entity);
+            }
+        }
+    }
+
+    /// Returns the EntityId of the first alive entity that satisfies the predicate `f`.
+    pub fn find(&self, f: impl Fn(EntityId<I, G>, &T) -> bool) -> Option<EntityId<I, G>> {
+        self.find_map(|id, entity| if f(id, entity) { Some(id) } else { None })
+    }
+
+    /// Returns the first non-None result of `f` applied to alive entities.
+    pub fn find_map<U>(&self, f: impl Fn(EntityId<I, G>, &T) -> Option<U>) -> Option<U> {
+        self.0.iter().enumerate().find_map(|(i, entry)| {
+            let id = EntityId {
+                id: I::from_usize(i),
+                gen: entry.gen,
+            };
+            f(id, entry.entity.as_ref()?)
+        })
+    }
+
+    /// Returns true if any alive entity satisfies the predicate `f`, stopping at the first one.
+    pub fn any(&self, f: impl Fn(EntityId<I, G>, &T) -> bool) -> bool {
+        self.find(f).is_some()
+    }
+
+    /// Returns true if all alive entities satisfy the predicate `f`, stopping at the first one
+    /// that does not. An empty list returns true.
+    pub fn all(&self, f: impl Fn(EntityId<I, G>, &T) -> bool) -> bool {
+        self.find(|id, entity| !f(id, entity)).is_none()
+    }
+
+    /// Returns the number of alive entities that satisfy the predicate `f`.
+    pub fn count_matching(&self, f: impl Fn(EntityId<I, G>, &T) -> bool) -> usize {
+        self.iter().filter(|(id, entity)| f(*id, entity)).count()
+    }
+
+    /// Removes the alive entity in the highest slot, which is O(1) if no entity was removed from
+    /// the end.
+    ///
+    /// Like `remove`, the slot's generation is bumped when it is reused, so the returned EntityId
+    /// will never point to another entity.
+    pub fn pop_any(&mut self) -> Option<(EntityId<I, G>, T)> {
+        let id = self.last_alive()?;
+        Some((id, self.remove(id)?))
+    }
+
+    /// Removes the alive entity in the lowest slot, which is O(1) if the first slot is alive.
+    pub fn pop_front(&mut self) -> Option<(EntityId<I, G>, T)> {
+        let id = self.first_alive()?;
+        Some((id, self.remove(id)?))
+    }
+
+    /// Returns the EntityId of the alive entity in the lowest slot.
+    ///
+    /// Note that "first" refers to the slot order, not the insertion order. They coincide
+    /// until a removed slot is reused by `add` or the list is compacted.
+    pub fn first_alive(&self) -> Option<EntityId<I, G>> {
+        self.0.iter().enumerate().find_map(|(i, entry)| {
+            entry.entity.as_ref()?;
+            Some(EntityId {
+                id: I::from_usize(i),
+                gen: entry.gen,
+            })
+        })
+    }
+
+    /// Returns the EntityId of the alive entity in the highest slot.
+    ///
+    /// See [`EntityList::first_alive`] for the meaning of the order.
+    pub fn last_alive(&self) -> Option<EntityId<I, G>> {
+        self.0.iter().enumerate().rev().find_map(|(i, entry)| {
+            entry.entity.as_ref()?;
+            Some(EntityId {
+                id: I::from_usize(i),
+                gen: entry.gen,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Entity, EntityId, EntityList, RemoveError};
+
+    #[test]
+    fn it_works() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        assert_eq!(b, EntityId { id: 1, gen: 0 });
+        el.remove(b);
+        assert_eq!(el.get(a), Some(&Entity { name: "a" }));
+        assert_eq!(el.get(b), None);
+        assert_eq!(el.get(c), Some(&Entity { name: "c" }));
         assert_eq!(el.0.len(), 3);
 
         let d = el.add(Entity { name: "d" });
@@ -196,4 +1952,1295 @@ mod tests {
         let _e = el.add(Entity { name: "e" });
         assert_eq!(el.get_pair_mut(d, a), (None, None));
     }
+
+    #[test]
+    fn sort() {
+        let mut el = EntityList::default();
+        let c = el.add(Entity { name: "c" });
+        let a = el.add(Entity { name: "a" });
+        let x = el.add(Entity { name: "x" });
+        let b = el.add(Entity { name: "b" });
+        el.remove(x);
+
+        let remap = el.sort_unstable_by(|l, r| l.name.cmp(r.name));
+        assert_eq!(remap.len(), 3);
+        assert_eq!(el.0.len(), 4);
+        assert!(el.0[3].entity.is_none());
+
+        let names: Vec<_> =
+            el.0.iter()
+                .filter_map(|e| e.entity.as_ref())
+                .map(|e| e.name)
+                .collect();
+        assert_eq!(names, ["a", "b", "c"]);
+
+        for (old, name) in [(a, "a"), (b, "b"), (c, "c")] {
+            let (_, new) = remap.iter().find(|(o, _)| *o == old).unwrap();
+            assert_eq!(el.get(old), None);
+            assert_eq!(el.get(*new), Some(&Entity { name }));
+        }
+    }
+
+    #[test]
+    fn find() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let _c = el.add(Entity { name: "b" });
+
+        assert_eq!(el.find(|_, e| e.name == "b"), Some(b));
+        assert_eq!(el.find(|id, _| id != a), Some(b));
+        assert_eq!(el.find(|_, e| e.name == "z"), None);
+
+        el.remove(a);
+        assert_eq!(el.find(|_, e| e.name == "a"), None);
+        assert_eq!(
+            el.find_map(|id, e| if e.name == "b" { Some(id.id) } else { None }),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn first_last_alive() {
+        let mut el = EntityList::default();
+        assert_eq!(el.first_alive(), None);
+        assert_eq!(el.last_alive(), None);
+
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        let d = el.add(Entity { name: "d" });
+        assert_eq!(el.first_alive(), Some(a));
+        assert_eq!(el.last_alive(), Some(d));
+
+        el.remove(a);
+        el.remove(d);
+        assert_eq!(el.first_alive(), Some(b));
+        assert_eq!(el.last_alive(), Some(c));
+
+        el.remove(b);
+        el.remove(c);
+        assert_eq!(el.first_alive(), None);
+        assert_eq!(el.last_alive(), None);
+    }
+
+    #[test]
+    fn try_add() {
+        let mut el = EntityList::default().with_max_entities(2);
+        let a = el.try_add(Entity { name: "a" }).unwrap();
+        let _b = el.try_add(Entity { name: "b" }).unwrap();
+        assert_eq!(el.try_add(Entity { name: "c" }), Err(Entity { name: "c" }));
+        assert_eq!(el.len(), 2);
+
+        el.remove(a);
+        let c = el.try_add(Entity { name: "c" }).unwrap();
+        assert_eq!(el.get(c), Some(&Entity { name: "c" }));
+
+        let mut unlimited = EntityList::default();
+        for name in ["a", "b", "c"] {
+            assert!(unlimited.try_add(Entity { name }).is_ok());
+        }
+    }
+
+    #[cfg(any(debug_assertions, feature = "strict-limits"))]
+    #[test]
+    #[should_panic]
+    fn add_over_limit() {
+        let mut el = EntityList::default().with_max_entities(1);
+        el.add(Entity { name: "a" });
+        el.add(Entity { name: "b" });
+    }
+
+    #[test]
+    fn pop() {
+        let mut el = EntityList::default();
+        assert_eq!(el.pop_any(), None);
+        assert_eq!(el.pop_front(), None);
+
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+
+        assert_eq!(el.pop_any(), Some((c, Entity { name: "c" })));
+        assert_eq!(el.pop_front(), Some((a, Entity { name: "a" })));
+        assert_eq!(el.get(a), None);
+        assert_eq!(el.get(c), None);
+
+        let d = el.add(Entity { name: "d" });
+        assert_ne!(d, a);
+        assert_eq!(el.get(a), None);
+
+        assert_eq!(el.pop_front(), Some((d, Entity { name: "d" })));
+        assert_eq!(el.pop_front(), Some((b, Entity { name: "b" })));
+        assert_eq!(el.pop_any(), None);
+    }
+
+    #[test]
+    fn slot_stats() {
+        let mut el = EntityList::default();
+        assert_eq!(el.fragmentation_ratio(), 1.);
+        assert_eq!(el.alive_fraction(), 0.);
+
+        let a = el.add(Entity { name: "a" });
+        let _b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        let _d = el.add(Entity { name: "d" });
+        el.remove(a);
+        el.remove(c);
+
+        assert_eq!(el.slot_count(), 4);
+        assert_eq!(el.free_slot_count(), 2);
+        assert_eq!(el.spare_capacity(), el.0.capacity() - 4);
+        assert_eq!(el.fragmentation_ratio(), 0.5);
+
+        el.add(Entity { name: "e" });
+        assert_eq!(el.slot_count(), 4);
+        assert_eq!(el.free_slot_count(), 1);
+        assert_eq!(el.fragmentation_ratio(), 0.25);
+        assert_eq!(el.alive_fraction(), 0.75);
+    }
+
+    #[test]
+    fn from_iter() {
+        let el: EntityList<_> = ["a", "b"].iter().map(|&name| Entity { name }).collect();
+        assert_eq!(el.len(), 2);
+
+        let (mut el, ids) =
+            EntityList::from_iter_with_ids(vec![Entity { name: "a" }, Entity { name: "b" }]);
+        assert_eq!(el.get(ids[0]), Some(&Entity { name: "a" }));
+        assert_eq!(el.get(ids[1]), Some(&Entity { name: "b" }));
+
+        el.remove(ids[0]);
+        el.extend(vec![Entity { name: "c" }, Entity { name: "d" }]);
+        assert_eq!(el.len(), 3);
+        assert_eq!(el.slot_count(), 3);
+        assert_eq!(el.find(|_, e| e.name == "c").map(|id| id.id), Some(0));
+    }
+
+    #[test]
+    fn index() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        assert_eq!(el[a], Entity { name: "a" });
+        el[a].name = "A";
+        assert_eq!(el[a].name, "A");
+        assert_eq!(a.to_string(), "#0:0");
+    }
+
+    #[test]
+    #[should_panic(expected = "EntityList: no entity with id #0:0")]
+    fn index_stale() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        el.remove(a);
+        let _ = &el[a];
+    }
+
+    #[test]
+    fn clone() {
+        let mut el = EntityList::default().with_max_entities(3);
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        el.remove(a);
+        let a2 = el.add(Entity { name: "a2" });
+
+        let mut cloned = el.clone();
+        assert_eq!(cloned.get(a), None);
+        assert_eq!(cloned.get(a2), Some(&Entity { name: "a2" }));
+        assert_eq!(cloned.get(b), Some(&Entity { name: "b" }));
+
+        cloned[b].name = "B";
+        cloned.remove(a2);
+        cloned.add(Entity { name: "c" });
+        assert_eq!(el.get(b), Some(&Entity { name: "b" }));
+        assert_eq!(el.get(a2), Some(&Entity { name: "a2" }));
+        assert_eq!(el.len(), 2);
+
+        cloned.add(Entity { name: "d" });
+        assert!(cloned.try_add(Entity { name: "e" }).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mut el = EntityList::default();
+        let a = el.add("a".to_string());
+        let b = el.add("b".to_string());
+        el.remove(a);
+        let c = el.add("c".to_string());
+        let d = el.add("d".to_string());
+        el.remove(d);
+
+        assert_eq!(
+            serde_json::to_string(&c).unwrap(),
+            r#"{"index":0,"generation":1}"#
+        );
+
+        let json = serde_json::to_string(&el).unwrap();
+        let de: EntityList<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(de.slot_count(), 3);
+        assert_eq!(de.get(a), None);
+        assert_eq!(de.get(b), Some(&"b".to_string()));
+        assert_eq!(de.get(c), Some(&"c".to_string()));
+        assert_eq!(de.get(d), None);
+    }
+
+    #[test]
+    fn try_remove() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        assert_eq!(el.try_remove(a), Ok(Entity { name: "a" }));
+        assert_eq!(el.try_remove(a), Err(RemoveError::SlotAlreadyEmpty));
+
+        let b = el.add(Entity { name: "b" });
+        assert_eq!(
+            el.try_remove(a),
+            Err(RemoveError::GenerationMismatch { current_gen: 1 })
+        );
+        assert_eq!(el.remove(a), None);
+        assert_eq!(el.get(b), Some(&Entity { name: "b" }));
+
+        assert_eq!(
+            el.try_remove(EntityId { id: 5, gen: 0 }),
+            Err(RemoveError::SlotOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn swap_remove_alive() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+
+        let (removed, moved) = el.swap_remove_alive(a).unwrap();
+        assert_eq!(removed, Entity { name: "a" });
+        let (old_c, new_c) = moved.unwrap();
+        assert_eq!(old_c, c);
+        assert_eq!(new_c, EntityId { id: 0, gen: 1 });
+        assert_eq!(el.get(a), None);
+        assert_eq!(el.get(c), None);
+        assert_eq!(el.get(new_c), Some(&Entity { name: "c" }));
+        assert_eq!(el.slot_count(), 2);
+
+        assert_eq!(el.swap_remove_alive(b), Some((Entity { name: "b" }, None)));
+        assert_eq!(el.swap_remove_alive(b), None);
+        assert_eq!(el.len(), 1);
+        assert_eq!(el.slot_count(), 1);
+
+        // Neither the removed EntityIds nor the old one of the moved entity revive.
+        let d = el.add(Entity { name: "d" });
+        let e = el.add(Entity { name: "e" });
+        assert_eq!(d, EntityId { id: 1, gen: 1 });
+        assert_eq!(e, EntityId { id: 2, gen: 1 });
+        assert_eq!(el.get(a), None);
+        assert_eq!(el.get(b), None);
+        assert_eq!(el.get(c), None);
+    }
+
+    #[test]
+    fn apply_to_many() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        el.remove(b);
+        let stale = EntityId { id: 9, gen: 0 };
+
+        let mut called = vec![];
+        el.apply_to_many(&[c, b, stale, a], |id, e| {
+            e.name = "x";
+            called.push(id);
+        });
+        assert_eq!(called, [c, a]);
+        assert_eq!(el.get(a), Some(&Entity { name: "x" }));
+        assert_eq!(el.get(c), Some(&Entity { name: "x" }));
+    }
+
+    #[test]
+    fn get_many() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        el.remove(b);
+
+        assert_eq!(
+            el.get_many([a, b, a]),
+            [
+                Some(&Entity { name: "a" }),
+                None,
+                Some(&Entity { name: "a" })
+            ]
+        );
+        assert_eq!(
+            el.get_many_slice(&[b, a]),
+            [None, Some(&Entity { name: "a" })]
+        );
+        assert!(el.get_many_slice(&[]).is_empty());
+    }
+
+    #[test]
+    fn position() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        assert_eq!(el.position(a), Some(0));
+        assert_eq!(el.position(b), Some(1));
+
+        el.remove(b);
+        assert_eq!(el.position(b), None);
+        el.add(Entity { name: "c" });
+        assert_eq!(el.position(b), None);
+        assert_eq!(el.position(EntityId { id: 5, gen: 0 }), None);
+    }
+
+    #[test]
+    fn get_current_id() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        assert_eq!(el.get_current_id(0), Some(a));
+
+        el.remove(a);
+        assert_eq!(el.get_current_id(0), None);
+        let c = el.add(Entity { name: "c" });
+        assert_eq!(el.get_current_id(0), Some(c));
+        assert_eq!(el.get_current_id(1).and_then(|id| el.position(id)), Some(1));
+        assert_eq!(el.get_current_id(1), Some(b));
+        assert_eq!(el.get_current_id(2), None);
+    }
+
+    #[test]
+    fn alive_ids_compact() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        el.remove(b);
+
+        let ids = el.alive_ids_compact();
+        assert_eq!(ids, el.ids().collect::<Vec<_>>());
+        assert_eq!(ids, [a, c]);
+        for id in ids {
+            el.remove(id);
+        }
+        assert!(el.is_empty());
+    }
+
+    #[test]
+    fn compact() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        let d = el.add(Entity { name: "d" });
+        el.remove(a);
+        el.remove(c);
+
+        let remap = el.compact();
+        assert_eq!(el.slot_count(), 2);
+        assert_eq!(
+            remap,
+            [
+                (b, EntityId { id: 0, gen: 1 }),
+                (d, EntityId { id: 1, gen: 1 })
+            ]
+        );
+        assert_eq!(el.get(remap[0].1), Some(&Entity { name: "b" }));
+        assert_eq!(el.get(remap[1].1), Some(&Entity { name: "d" }));
+        assert_eq!(el.get(b), None);
+        assert!(el.compact().is_empty());
+
+        // The generations of the dropped slots carry over to the slots pushed again.
+        let e = el.add(Entity { name: "e" });
+        let f = el.add(Entity { name: "f" });
+        assert_eq!(e, EntityId { id: 2, gen: 1 });
+        assert_eq!(f, EntityId { id: 3, gen: 1 });
+        assert_eq!(el.get(c), None);
+        assert_eq!(el.get(d), None);
+    }
+
+    #[test]
+    fn tombstone_limit() {
+        use std::sync::{Arc, Mutex};
+
+        let mut el = EntityList::default().with_tombstone_limit(1);
+        let remapped = Arc::new(Mutex::new(vec![]));
+        let remapped2 = remapped.clone();
+        el.on_compact(move |remap| remapped2.lock().unwrap().extend_from_slice(remap));
+
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        el.remove(a);
+        assert_eq!(el.slot_count(), 3);
+        assert!(remapped.lock().unwrap().is_empty());
+
+        el.remove(b);
+        assert_eq!(el.slot_count(), 1);
+        let remapped = remapped.lock().unwrap();
+        assert_eq!(remapped.len(), 1);
+        assert_eq!(remapped[0].0, c);
+        assert_eq!(el.get(remapped[0].1), Some(&Entity { name: "c" }));
+    }
+
+    #[test]
+    fn iter_dead() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let _b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        assert_eq!(el.iter_dead().count(), 0);
+
+        el.remove(a);
+        el.remove(c);
+        assert_eq!(el.iter_dead().collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(el.count_dead(), 2);
+        assert_eq!(el.iter_dead().count() + el.len(), el.slot_count());
+    }
+
+    #[test]
+    fn new() {
+        let mut el = EntityList::new();
+        assert!(el.is_empty());
+        el.add(Entity { name: "a" });
+        assert_eq!(el.len(), 1);
+
+        let el = EntityList::<Entity>::with_capacity(10);
+        assert!(el.spare_capacity() >= 10);
+    }
+
+    #[test]
+    fn from_entities() {
+        let (el, ids) = EntityList::from_entities(vec![Entity { name: "a" }, Entity { name: "b" }]);
+        assert_eq!(
+            ids,
+            [EntityId { id: 0, gen: 0 }, EntityId { id: 1, gen: 0 }]
+        );
+        assert_eq!(el.get(ids[1]), Some(&Entity { name: "b" }));
+
+        let el = EntityList::from_entities_no_ids(vec![Entity { name: "a" }]);
+        assert_eq!(el.len(), 1);
+        assert_eq!(el.slot_count(), 1);
+    }
+
+    #[test]
+    fn get_pair_ref() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+
+        assert_eq!(
+            el.get_pair_ref(a, b),
+            (Some(&Entity { name: "a" }), Some(&Entity { name: "b" }))
+        );
+        assert_eq!(
+            el.get_pair_ref(b, c),
+            (Some(&Entity { name: "b" }), Some(&Entity { name: "c" }))
+        );
+        assert_eq!(
+            el.get_pair_ref(c, a),
+            (Some(&Entity { name: "c" }), Some(&Entity { name: "a" }))
+        );
+
+        el.remove(a);
+
+        let d = el.add(Entity { name: "d" });
+
+        assert_eq!(el.get_pair_ref(d, a), (Some(&Entity { name: "d" }), None));
+
+        el.remove(d);
+
+        let _e = el.add(Entity { name: "e" });
+        assert_eq!(el.get_pair_ref(d, a), (None, None));
+    }
+
+    #[test]
+    fn scan() {
+        let mut el = EntityList::default();
+        let a = el.add(1);
+        let b = el.add(2);
+        let c = el.add(3);
+        let _d = el.add(4);
+        el.remove(b);
+
+        let prefix_sum: Vec<_> = el
+            .scan(0, |sum, _, v| {
+                *sum += v;
+                Some(*sum)
+            })
+            .collect();
+        assert_eq!(prefix_sum, [1, 4, 8]);
+
+        let until_c: Vec<_> = el
+            .scan(vec![], |contributors, id, _| {
+                if id == c {
+                    return None;
+                }
+                contributors.push(id);
+                Some(contributors.clone())
+            })
+            .collect();
+        assert_eq!(until_c, [vec![a]]);
+    }
+
+    #[test]
+    fn fold_ids() {
+        let mut el = EntityList::default();
+        let a = el.add(1);
+        let b = el.add(2);
+        let c = el.add(3);
+        el.remove(a);
+
+        assert_eq!(el.fold_ids(0, |acc, _, v| acc + v), 5);
+        assert_eq!(
+            el.fold_ids(vec![], |mut acc, id, _| {
+                acc.push(id);
+                acc
+            }),
+            [b, c]
+        );
+
+        let count = el.fold_ids_mut(0, |acc, _, v| {
+            *v *= 10;
+            acc + 1
+        });
+        assert_eq!(count, 2);
+        assert_eq!(el.get_many([b, c]), [Some(&20), Some(&30)]);
+    }
+
+    #[test]
+    fn zip_with() {
+        let mut names = EntityList::default();
+        let mut values = EntityList::default();
+        let mut ids = vec![];
+        for (name, value) in [("a", 1), ("b", 2), ("c", 3)] {
+            ids.push(names.add(Entity { name }));
+            values.add(value);
+        }
+        names.add(Entity { name: "d" });
+        names.remove(ids[0]);
+        values.remove(ids[1]);
+
+        let zipped: Vec<_> = names
+            .zip_with(&values)
+            .map(|(id, e, v)| (id, e.name, *v))
+            .collect();
+        assert_eq!(zipped, [(ids[2], "c", 3)]);
+    }
+
+    #[test]
+    fn iter_from() {
+        let mut el = EntityList::default();
+        let ids: Vec<_> = (0..5).map(|i| el.add(i)).collect();
+        el.remove(ids[3]);
+
+        // Process 2 entities per frame, resuming from where the previous frame left off.
+        let mut processed = vec![];
+        let mut next = el.first_alive();
+        while let Some(start) = next {
+            let mut iter = el.iter_from(start);
+            processed.extend(iter.by_ref().take(2).map(|(_, v)| *v));
+            next = iter.next().map(|(id, _)| id);
+        }
+        assert_eq!(processed, [0, 1, 2, 4]);
+
+        assert_eq!(el.iter_from(ids[3]).next(), Some((ids[4], &4)));
+        assert_eq!(el.iter_from(EntityId { id: 5, gen: 0 }).next(), None);
+    }
+
+    #[test]
+    fn iter_with_neighbors() {
+        let mut el = EntityList::default();
+        let ids: Vec<_> = (0..5).map(|i| el.add(i)).collect();
+        el.remove(ids[0]);
+        el.remove(ids[2]);
+        el.remove(ids[4]);
+
+        assert_eq!(
+            el.iter_with_neighbors().collect::<Vec<_>>(),
+            [(None, ids[1], Some(ids[3])), (Some(ids[1]), ids[3], None)]
+        );
+
+        el.remove(ids[3]);
+        assert_eq!(
+            el.iter_with_neighbors().collect::<Vec<_>>(),
+            [(None, ids[1], None)]
+        );
+    }
+
+    #[test]
+    fn gen_width() {
+        use super::{EntityEntry, EntityList8};
+        use std::mem::size_of;
+
+        let mut el = EntityList8::default();
+        let a = el.add(Entity { name: "a" });
+        let mut b = a;
+        for _ in 0..u8::MAX - 1 {
+            el.remove(b);
+            b = el.add(Entity { name: "b" });
+        }
+        assert_eq!(el.get(a), None);
+        assert_eq!(
+            b,
+            EntityId {
+                id: 0,
+                gen: u8::MAX - 1
+            }
+        );
+
+        // The exhausted slot is retired instead of wrapping around to a stale generation.
+        el.remove(b);
+        assert_eq!(el.free_slot_count(), 0);
+        assert_eq!(el.count_dead(), 1);
+        let c = el.add(Entity { name: "c" });
+        assert_eq!(c, EntityId { id: 1, gen: 0 });
+        assert_eq!(el.get(b), None);
+        assert_eq!(el.get(a), None);
+        assert_eq!(el.validate(), Ok(()));
+
+        el.compact();
+        assert_eq!(el.get_current_id(1), Some(c));
+        assert_eq!(el.validate(), Ok(()));
+
+        assert!(size_of::<EntityEntry<u16, u8>>() < size_of::<EntityEntry<u16>>());
+    }
+
+    #[test]
+    fn small_entity_list() {
+        use super::{SmallEntityId, SmallEntityList};
+        use std::mem::size_of;
+
+        assert_eq!(size_of::<SmallEntityId>(), 4);
+        let mut el = SmallEntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        el.remove(a);
+        let c = el.add(Entity { name: "c" });
+        assert_eq!(el.get(a), None);
+        assert_eq!(el[b].name, "b");
+        assert_eq!(el.get_current_id(0), Some(c));
+        assert_eq!(c.to_string(), "#0:1");
+
+        let mut el: SmallEntityList<_> = (0..u16::MAX).collect();
+        let last = el.add(1);
+        assert_eq!(last.id, u16::MAX);
+        assert_eq!(el.try_add(2), Err(2));
+        assert_eq!(el.slot_count(), u16::MAX as usize + 1);
+        assert_eq!(el.iter().count(), u16::MAX as usize + 1);
+        el.remove(last);
+        assert!(el.try_add(3).is_ok());
+    }
+
+    /// A list whose generation floor is exhausted, since a slot that cannot be bumped any more
+    /// was split off.
+    fn list_without_slots() -> super::EntityList8<Entity> {
+        let mut el = super::EntityList8::default();
+        let mut a = el.add(Entity { name: "a" });
+        for _ in 0..u8::MAX - 1 {
+            el.remove(a);
+            a = el.add(Entity { name: "a" });
+        }
+        let _tail = el.split_off_at(0);
+        el
+    }
+
+    #[test]
+    fn try_add_without_slots() {
+        let mut el = list_without_slots();
+        assert_eq!(el.try_add(Entity { name: "b" }), Err(Entity { name: "b" }));
+        assert_eq!(el.slot_count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "EntityList: no slot left for a new entity")]
+    fn add_without_slots() {
+        list_without_slots().add(Entity { name: "b" });
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn small_entity_list_serde() {
+        use super::SmallEntityList;
+
+        let el: EntityList<u8> = vec![0; 70_000].into_iter().collect();
+        let json = serde_json::to_string(&el).unwrap();
+        let err = serde_json::from_str::<SmallEntityList<u8>>(&json)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("70000 slots"));
+
+        let el: EntityList<u8> = vec![0; 3].into_iter().collect();
+        let json = serde_json::to_string(&el).unwrap();
+        let de: SmallEntityList<u8> = serde_json::from_str(&json).unwrap();
+        assert_eq!(de.len(), 3);
+        let json = json.replace(r#""alive":3"#, r#""alive":5"#);
+        assert!(serde_json::from_str::<SmallEntityList<u8>>(&json).is_err());
+    }
+
+    #[test]
+    fn into_vec_with_ids() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        el.remove(b);
+        assert_eq!(
+            el.clone().into_values(),
+            [Entity { name: "a" }, Entity { name: "c" }]
+        );
+        assert_eq!(
+            el.into_vec_with_ids(),
+            [(a, Entity { name: "a" }), (c, Entity { name: "c" })]
+        );
+    }
+
+    #[test]
+    fn raw_slots() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        el.add(Entity { name: "b" });
+        el.remove(a);
+        el.add(Entity { name: "c" });
+        el.remove(a);
+
+        let slots = el.raw_slots();
+        assert_eq!(slots.len(), 2);
+        assert_eq!(slots[0].generation(), 1);
+        assert_eq!(slots[0].value(), Some(&Entity { name: "c" }));
+
+        el.raw_slots_mut()[1].value_mut().unwrap().name = "bb";
+        assert_eq!(el.raw_slots()[1].value(), Some(&Entity { name: "bb" }));
+    }
+
+    #[test]
+    fn get_two_mut() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let (ea, eb) = el.get_two_mut(a, b);
+        std::mem::swap(&mut ea.name, &mut eb.name);
+        assert_eq!(el[a].name, "b");
+        assert_eq!(el[b].name, "a");
+    }
+
+    #[test]
+    #[should_panic(expected = "no entity with id #1:0")]
+    fn get_two_mut_stale() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        el.remove(b);
+        el.get_two_mut(a, b);
+    }
+
+    #[test]
+    fn map() {
+        let mut el = EntityList::default().with_max_entities(3);
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        el.remove(b);
+
+        let mut mapped = el.map(|id, e| (id, e.name.to_uppercase()));
+        assert_eq!(mapped[a], (a, "A".to_string()));
+        assert_eq!(mapped[c], (c, "C".to_string()));
+        assert_eq!(mapped.get(b), None);
+        assert_eq!(mapped.slot_count(), 3);
+        let d = mapped.add((a, "D".to_string()));
+        assert_eq!(d.id, b.id);
+        assert_eq!(
+            mapped.try_add((a, "E".to_string())),
+            Err((a, "E".to_string()))
+        );
+    }
+
+    #[test]
+    fn filter_map() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        el.remove(c);
+
+        let mut filtered = el.filter_map(|_, e| if e.name == "b" { None } else { Some(e.name) });
+        assert_eq!(filtered.get(a), Some(&"a"));
+        assert_eq!(filtered.get(b), None);
+        assert_eq!(filtered.raw_slots()[1].generation(), 0);
+        assert_eq!(filtered.raw_slots()[2].generation(), 0);
+        let d = filtered.add("d");
+        assert_eq!((d.id, d.gen), (1, 1));
+        assert_eq!(filtered.get(b), None);
+    }
+
+    #[test]
+    fn enumerate_alive() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        el.remove(b);
+        let names = ["x", "y"];
+        for (i, _, e) in el.enumerate_alive_mut() {
+            e.name = names[i];
+        }
+        assert_eq!(
+            el.enumerate_alive().collect::<Vec<_>>(),
+            [(0, a, &Entity { name: "x" }), (1, c, &Entity { name: "y" })]
+        );
+    }
+
+    #[test]
+    fn iter_chunks() {
+        let mut el: EntityList<_> = (0..5).collect();
+        let id = el.get_current_id(1).unwrap();
+        el.remove(id);
+        for chunk in el.iter_chunks_mut(2) {
+            for value in chunk.iter_mut().filter_map(|entry| entry.value_mut()) {
+                *value *= 10;
+            }
+        }
+        let sums: Vec<i32> = el
+            .iter_chunks(2)
+            .map(|chunk| chunk.iter().filter_map(|entry| entry.value()).sum())
+            .collect();
+        assert_eq!(sums, [0, 50, 40]);
+    }
+
+    #[test]
+    fn windows_alive() {
+        let mut el: EntityList<_> = (0..6).collect();
+        let ids: Vec<_> = el.ids().collect();
+        el.remove(ids[1]);
+        el.remove(ids[4]);
+        let windows: Vec<Vec<_>> = el
+            .windows_alive(2)
+            .map(|window| window.into_iter().map(|(_, v)| *v).collect())
+            .collect();
+        assert_eq!(windows, [[0, 2], [2, 3], [3, 5]]);
+        assert_eq!(el.windows_alive(3).next().unwrap()[2].0, ids[3]);
+        assert_eq!(el.windows_alive(5).count(), 0);
+    }
+
+    #[test]
+    fn rotate_alive_left() {
+        let mut el: EntityList<_> = (0..4).collect();
+        let ids: Vec<_> = el.ids().collect();
+        el.remove(ids[1]);
+        assert_eq!(el.rotate_alive_left(3), []);
+
+        let remap = el.rotate_alive_left(1);
+        assert_eq!(el.iter().map(|(_, v)| *v).collect::<Vec<_>>(), [2, 3, 0]);
+        assert_eq!(el.get(ids[1]), None);
+        assert_eq!(el.raw_slots()[1].generation(), 0);
+        assert_eq!(remap.len(), 3);
+        for (old, new) in remap {
+            assert_eq!(el[new], old.id);
+        }
+    }
+
+    #[test]
+    fn dedup_by() {
+        let mut el: EntityList<_> = [1, 1, 2, 0, 2, 3, 3].iter().copied().collect();
+        let ids: Vec<_> = el.ids().collect();
+        el.remove(ids[3]);
+        assert_eq!(el.dedup_by_key(|v| *v), [ids[1], ids[4], ids[6]]);
+        assert_eq!(el.iter().map(|(_, v)| *v).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(el.get(ids[1]), None);
+
+        let removed = el.dedup_by(|a, b| {
+            *b += *a;
+            true
+        });
+        assert_eq!(removed, [ids[2], ids[5]]);
+        assert_eq!(el[ids[0]], 6);
+
+        let mut el = [1, 1, 1, 2]
+            .iter()
+            .copied()
+            .collect::<EntityList<_>>()
+            .with_tombstone_limit(1);
+        el.on_compact(|_| {});
+        let ids: Vec<_> = el.ids().collect();
+        assert_eq!(el.dedup_by_key(|v| *v), [ids[1], ids[2]]);
+        assert_eq!(el.slot_count(), 2);
+        assert_eq!(el.iter().map(|(_, v)| *v).collect::<Vec<_>>(), [1, 2]);
+    }
+
+    #[test]
+    fn chain_iter() {
+        let mut el: EntityList<_> = (0..3).collect();
+        let other: EntityList<_> = (10..12).collect();
+        let id = el.get_current_id(1).unwrap();
+        el.remove(id);
+
+        let chained: Vec<_> = el.chain_iter(&other).collect();
+        assert_eq!(chained.len(), el.len() + other.len());
+        let (first, second) = chained.split_at(el.len());
+        assert!(first.iter().all(|(id, v)| el.get(*id) == Some(*v)));
+        assert!(second.iter().all(|(id, v)| other.get(*id) == Some(*v)));
+    }
+
+    #[test]
+    fn split_off_at() {
+        let mut el: EntityList<_> = (0..5).collect();
+        let ids: Vec<_> = el.ids().collect();
+        el.remove(ids[1]);
+        el.remove(ids[3]);
+        let mut tail = el.split_off_at(2);
+
+        assert_eq!(el.slot_count(), 2);
+        assert_eq!(el.ids().collect::<Vec<_>>(), [ids[0]]);
+        assert_eq!(tail.ids().collect::<Vec<_>>(), [ids[2], ids[4]]);
+        assert_eq!(tail.get(ids[0]), None);
+        assert_eq!(el.get(ids[2]), None);
+        assert_eq!(tail.len(), 2);
+
+        let id = tail.add(5);
+        assert_eq!(id.id, 0);
+        assert_eq!(tail.get(ids[2]), Some(&2));
+    }
+
+    #[test]
+    fn get_two_entries_mut() {
+        let mut el: EntityList<_> = (0..3).collect();
+        let id = el.get_current_id(2).unwrap();
+        el.remove(id);
+        let (a, b) = el.get_two_entries_mut(2, 0).unwrap();
+        assert_eq!((a.value(), b.value()), (None, Some(&0)));
+        *b.value_mut().unwrap() = 10;
+        assert_eq!(el.get_two_entries_mut(1, 1).map(|_| ()), None);
+        assert_eq!(el.get_two_entries_mut(1, 3).map(|_| ()), None);
+        assert_eq!(el.raw_slots()[0].value(), Some(&10));
+    }
+
+    #[test]
+    fn alive_at() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        assert!(el.alive_at(0));
+        el.remove(a);
+        assert!(!el.alive_at(0));
+        el.add(Entity { name: "b" });
+        assert!(el.alive_at(0));
+        assert!(!el.alive_at(1));
+    }
+
+    #[test]
+    fn iter_range() {
+        let mut el: EntityList<_> = (0..6).collect();
+        let ids: Vec<_> = el.ids().collect();
+        el.remove(ids[2]);
+        assert_eq!(el.count_in_range(1..4), 2);
+        assert_eq!(el.count_in_range(..), 5);
+        assert_eq!(
+            el.iter_range(1..=3).collect::<Vec<_>>(),
+            [(ids[1], &1), (ids[3], &3)]
+        );
+        assert_eq!(el.iter_range(4..).count(), 2);
+    }
+
+    #[test]
+    fn get_three_mut() {
+        let mut el: EntityList<_> = (0..3).collect();
+        let ids: Vec<_> = el.ids().collect();
+        let orders = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        for order in &orders {
+            let [i, j, k] = *order;
+            let (a, b, c) = el.get_three_mut(ids[i], ids[j], ids[k]);
+            assert_eq!(
+                (a.copied(), b.copied(), c.copied()),
+                (Some(i), Some(j), Some(k))
+            );
+        }
+
+        // Every combination of slots, where only the first EntityId of a slot gets the entity
+        for i in 0..3 {
+            for j in 0..3 {
+                for k in 0..3 {
+                    let (a, b, c) = el.get_three_mut(ids[i], ids[j], ids[k]);
+                    let expected = (
+                        Some(i),
+                        Some(j).filter(|&j| j != i),
+                        Some(k).filter(|&k| k != i && k != j),
+                    );
+                    assert_eq!((a.copied(), b.copied(), c.copied()), expected);
+                }
+            }
+        }
+
+        let (a, b, c) = el.get_three_mut(ids[0], ids[1], ids[0]);
+        assert_eq!((a.copied(), b.copied(), c), (Some(0), Some(1), None));
+        let (a, b, c) = el.get_three_mut(ids[2], ids[2], ids[1]);
+        assert_eq!((a.copied(), b, c.copied()), (Some(2), None, Some(1)));
+        let (a, b, c) = el.get_three_mut(ids[1], ids[0], ids[0]);
+        assert_eq!((a.copied(), b.copied(), c), (Some(1), Some(0), None));
+        let (a, b, c) = el.get_three_mut(ids[1], ids[1], ids[1]);
+        assert_eq!((a.copied(), b, c), (Some(1), None, None));
+
+        el.remove(ids[0]);
+        let d = el.add(3);
+        let (a, b, c) = el.get_three_mut(ids[0], d, ids[0]);
+        assert_eq!((a, b.copied(), c), (None, Some(3), None));
+    }
+
+    #[test]
+    fn retain_collect() {
+        let mut el: EntityList<_> = (0..5).collect();
+        let ids: Vec<_> = el.ids().collect();
+        el.remove(ids[1]);
+        let collected = el.retain_collect(|id, v| {
+            *v *= 10;
+            if *v == 20 {
+                None
+            } else {
+                Some(id)
+            }
+        });
+        assert_eq!(collected, [ids[0], ids[3], ids[4]]);
+        assert_eq!(el.iter().map(|(_, v)| *v).collect::<Vec<_>>(), [0, 30, 40]);
+        assert_eq!(el.get(ids[2]), None);
+    }
+
+    #[test]
+    fn iter_alive_windows_mut() {
+        let mut el: EntityList<_> = (0..5).collect();
+        let ids: Vec<_> = el.ids().collect();
+        el.remove(ids[2]);
+        let mut seen = vec![];
+        el.iter_alive_windows_mut(3, |window| {
+            seen.push(window.iter().map(|(id, _)| *id).collect::<Vec<_>>());
+            *window[1].1 += *window[0].1 + *window[2].1;
+        });
+        assert_eq!(seen, [[ids[0], ids[1], ids[3]], [ids[1], ids[3], ids[4]]]);
+        assert_eq!(
+            el.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+            [0, 4, 11, 4]
+        );
+
+        let mut count = 0;
+        el.iter_alive_windows_mut(5, |_| count += 1);
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn hashmap() {
+        use super::{FromHashMapError, SmallEntityList};
+        use std::collections::HashMap;
+
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        el.remove(a);
+        let c = el.add(Entity { name: "c" });
+        el.add(Entity { name: "d" });
+        let d = el.last_alive().unwrap();
+        el.remove(b);
+        assert_eq!((c.index(), c.generation()), (0, 1));
+
+        let map = el.clone().into_hashmap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&d], Entity { name: "d" });
+
+        let mut rebuilt = EntityList::from_hashmap(map.clone()).unwrap();
+        assert_eq!(
+            rebuilt.iter().collect::<Vec<_>>(),
+            el.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(rebuilt.get(a), None);
+        assert_eq!(rebuilt.add(Entity { name: "e" }).index(), 1);
+
+        let limited = EntityList::from_hashmap_with_max_entities(map.clone(), 3).unwrap();
+        assert_eq!(limited.entry_limit(), Some(3));
+        assert_eq!(
+            EntityList::from_hashmap_with_max_entities(map, 2).err(),
+            Some(FromHashMapError::IndexOutOfRange(d))
+        );
+
+        let huge = EntityId {
+            id: u16::MAX,
+            gen: 0,
+        };
+        let map: HashMap<_, _> = [(huge, 0)].iter().copied().collect();
+        assert_eq!(
+            SmallEntityList::from_hashmap(map).unwrap().slot_count(),
+            65536
+        );
+        let exhausted = EntityId {
+            id: 0,
+            gen: u32::MAX,
+        };
+        let map: HashMap<_, _> = [(exhausted, 0)].iter().copied().collect();
+        assert_eq!(
+            EntityList::from_hashmap(map).err(),
+            Some(FromHashMapError::ExhaustedGeneration(exhausted))
+        );
+    }
+
+    #[test]
+    fn entry_limit() {
+        let mut el = EntityList::default().with_entry_limit(2);
+        assert_eq!(el.entry_limit(), Some(2));
+        let a = el.add(Entity { name: "a" });
+        el.add(Entity { name: "b" });
+        assert_eq!(el.try_add(Entity { name: "c" }), Err(Entity { name: "c" }));
+        el.remove(a);
+        assert!(el.try_add(Entity { name: "c" }).is_ok());
+        assert_eq!(EntityList::<Entity>::default().entry_limit(), None);
+    }
+
+    #[test]
+    fn any_all_count_matching() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        el.add(Entity { name: "b" });
+
+        assert!(el.any(|_, e| e.name == "a"));
+        assert!(!el.any(|_, e| e.name == "z"));
+        assert!(el.all(|_, e| !e.name.is_empty()));
+        assert!(!el.all(|id, _| id == a));
+        assert_eq!(el.count_matching(|_, e| e.name == "b"), 2);
+        assert_eq!(el.count_matching(|id, _| id != b), 2);
+
+        let calls = std::cell::Cell::new(0);
+        assert!(el.any(|_, _| {
+            calls.set(calls.get() + 1);
+            true
+        }));
+        assert_eq!(calls.get(), 1);
+
+        el.remove(a);
+        assert!(!el.any(|_, e| e.name == "a"));
+        assert!(el.all(|_, e| e.name == "b"));
+
+        let empty = EntityList::<Entity>::default();
+        assert!(!empty.any(|_, _| true));
+        assert!(empty.all(|_, _| false));
+        assert_eq!(empty.count_matching(|_, _| true), 0);
+    }
+
+    #[test]
+    fn add_stable() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        el.remove(c);
+        el.remove(a);
+        let c = el.add(Entity { name: "c" });
+        el.remove(b);
+        let saved: Vec<_> = el.iter().map(|(id, e)| (id, e.name)).collect();
+
+        // The generation is ahead of the fresh slot's
+        let mut loaded = EntityList::default();
+        for &(id, name) in saved.iter().rev() {
+            assert_eq!(loaded.add_stable(Entity { name }, id), Ok(id));
+        }
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded.slot_count(), 1);
+        assert_eq!(loaded[c].name, "c");
+        assert_eq!(c.generation(), 1);
+
+        let far = EntityId { id: 3, gen: 2 };
+        assert_eq!(loaded.add_stable(Entity { name: "far" }, far), Ok(far));
+        assert_eq!(loaded.slot_count(), 4);
+        assert_eq!(loaded[far].name, "far");
+
+        // Occupied slot
+        let d = loaded.add_stable(Entity { name: "d" }, c);
+        assert_eq!(d, Err(EntityId { id: 1, gen: 1 }));
+        assert_eq!(loaded[c].name, "c");
+
+        // The generation equals the dead slot's
+        loaded.remove(far);
+        assert_eq!(loaded.add_stable(Entity { name: "far" }, far), Ok(far));
+
+        // The generation is behind the slot's
+        loaded.remove(far);
+        let e = loaded.add_stable(Entity { name: "e" }, EntityId { id: 3, gen: 1 });
+        assert_eq!(e, Err(EntityId { id: 3, gen: 3 }));
+    }
+
+    #[test]
+    fn try_add_stable() {
+        use super::AddStableError;
+
+        // Beyond the limit, which bounds the dead slots, and in a full list
+        let mut el = EntityList::default().with_max_entities(2);
+        let huge = EntityId {
+            id: u32::MAX,
+            gen: 0,
+        };
+        let a = el.try_add_stable(Entity { name: "a" }, huge);
+        assert_eq!(
+            a,
+            Err(AddStableError::Relocated(EntityId { id: 0, gen: 0 }))
+        );
+        assert_eq!(el.slot_count(), 1);
+        let b = EntityId { id: 1, gen: 3 };
+        assert_eq!(el.try_add_stable(Entity { name: "b" }, b), Ok(b));
+        assert_eq!(
+            el.try_add_stable(Entity { name: "c" }, huge),
+            Err(AddStableError::Full(Entity { name: "c" }))
+        );
+    }
+
+    #[test]
+    fn free_list() {
+        let mut el: EntityList<_> = (0..6).collect();
+        let ids: Vec<_> = el.ids().collect();
+        el.remove(ids[1]);
+        el.remove(ids[4]);
+        assert_eq!(el.len(), 4);
+        assert_eq!(el.free_slot_count(), 2);
+        // The most recently vacated slot is reused first
+        assert_eq!(el.add(6).index(), 4);
+        el.remove(ids[2]);
+        assert_eq!(el.validate(), Ok(()));
+
+        el.sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(el.validate(), Ok(()));
+        el.rotate_alive_left(1);
+        el.swap_remove_alive(el.first_alive().unwrap());
+        assert_eq!(el.validate(), Ok(()));
+        el.dedup_by(|_, _| false);
+        let mut tail = el.split_off_at(1);
+        assert_eq!(el.validate(), Ok(()));
+        assert_eq!(tail.validate(), Ok(()));
+        assert_eq!(tail.add(7).index(), 0);
+        let mut el = tail.filter_map(|_, v| if v == 7 { None } else { Some(v) });
+        assert_eq!(el.validate(), Ok(()));
+        let far = EntityId { id: 8, gen: 5 };
+        assert_eq!(el.add_stable(8, far), Ok(far));
+        assert_eq!(el.validate(), Ok(()));
+        el.compact();
+        assert_eq!(el.validate(), Ok(()));
+        assert_eq!(el.len(), el.iter().count());
+        assert_eq!(el.free_slot_count(), el.count_dead());
+    }
+
+    #[test]
+    fn unwind_safe() {
+        fn assert_unwind_safe<T: std::panic::UnwindSafe + std::panic::RefUnwindSafe>() {}
+        assert_unwind_safe::<EntityList<Entity>>();
+
+        let mut el = EntityList::default();
+        el.add(Entity { name: "a" });
+        el = std::panic::catch_unwind(move || {
+            el.on_compact(|_| {});
+            el
+        })
+        .unwrap();
+        assert_eq!(el.len(), 1);
+    }
 }
@@ -0,0 +1,56 @@
+use crate::EntityList;
+use std::ops::{Deref, DerefMut};
+
+/// An EntityList paired with user-defined metadata, such as a name or a version counter.
+///
+/// It dereferences to the list, so all the methods of EntityList are available directly.
+#[derive(Clone, Default)]
+pub struct EntityListWithMeta<T, M> {
+    pub list: EntityList<T>,
+    pub meta: M,
+}
+
+impl<T, M> Deref for EntityListWithMeta<T, M> {
+    type Target = EntityList<T>;
+
+    fn deref(&self) -> &EntityList<T> {
+        &self.list
+    }
+}
+
+impl<T, M> DerefMut for EntityListWithMeta<T, M> {
+    fn deref_mut(&mut self) -> &mut EntityList<T> {
+        &mut self.list
+    }
+}
+
+impl<T, M> From<(EntityList<T>, M)> for EntityListWithMeta<T, M> {
+    fn from((list, meta): (EntityList<T>, M)) -> Self {
+        Self { list, meta }
+    }
+}
+
+impl<T, M> From<EntityListWithMeta<T, M>> for (EntityList<T>, M) {
+    fn from(with_meta: EntityListWithMeta<T, M>) -> Self {
+        (with_meta.list, with_meta.meta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EntityListWithMeta;
+    use crate::{Entity, EntityList};
+
+    #[test]
+    fn with_meta() {
+        let mut el: EntityListWithMeta<_, &str> = (EntityList::default(), "region").into();
+        let a = el.add(Entity { name: "a" });
+        el.meta = "renamed";
+        assert_eq!(el.len(), 1);
+        assert_eq!(el[a].name, "a");
+
+        let (list, meta): (EntityList<_>, _) = el.into();
+        assert_eq!(list.get(a), Some(&Entity { name: "a" }));
+        assert_eq!(meta, "renamed");
+    }
+}
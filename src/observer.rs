@@ -0,0 +1,105 @@
+#[cfg(doc)]
+use crate::EntityList;
+use crate::{EntityEntry, EntityId, EntityListImpl, GenWidth, IndexWidth};
+use std::panic::{RefUnwindSafe, UnwindSafe};
+
+type ObserverCallback<T, I, G> =
+    Box<dyn Fn(EntityId<I, G>, &T) + Send + Sync + UnwindSafe + RefUnwindSafe>;
+
+/// Callbacks notified when entities enter or leave an EntityList.
+pub(crate) struct Observers<T, I, G> {
+    on_add: Vec<ObserverCallback<T, I, G>>,
+    on_remove: Vec<ObserverCallback<T, I, G>>,
+}
+
+impl<T, I, G> Default for Observers<T, I, G> {
+    fn default() -> Self {
+        Self {
+            on_add: vec![],
+            on_remove: vec![],
+        }
+    }
+}
+
+impl<T, I: IndexWidth, G: GenWidth> Observers<T, I, G> {
+    pub(crate) fn notify_add(&self, id: EntityId<I, G>, entry: &EntityEntry<T, G>) {
+        if let Some(entity) = &entry.entity {
+            self.on_add.iter().for_each(|f| f(id, entity));
+        }
+    }
+
+    pub(crate) fn notify_remove(&self, id: EntityId<I, G>, entry: &EntityEntry<T, G>) {
+        if let Some(entity) = &entry.entity {
+            self.on_remove.iter().for_each(|f| f(id, entity));
+        }
+    }
+}
+
+impl<T, I: IndexWidth, G: GenWidth> EntityListImpl<T, I, G> {
+    /// Registers a callback invoked after an entity is added by [`EntityList::add`] and its
+    /// variants.
+    ///
+    /// Like [`EntityList::on_compact`], the callbacks must be `Send + Sync` as well as unwind
+    /// safe, so that the list keeps these auto traits. This holds even when the `sync` feature is
+    /// disabled: features are additive, so a dependency enabling `observer` must not make the
+    /// list unusable across threads elsewhere. Share state through e.g. `Arc<Mutex<_>>` or
+    /// atomics rather than `Rc<RefCell<_>>`.
+    pub fn on_add(
+        &mut self,
+        f: impl Fn(EntityId<I, G>, &T) + Send + Sync + UnwindSafe + RefUnwindSafe + 'static,
+    ) {
+        self.1.observers.on_add.push(Box::new(f));
+    }
+
+    /// Registers a callback invoked before an entity is taken out of its slot by
+    /// [`EntityList::remove`] and its variants.
+    ///
+    /// Entities moved to other slots by [`EntityList::compact`] are not removed and do not
+    /// trigger it.
+    pub fn on_remove(
+        &mut self,
+        f: impl Fn(EntityId<I, G>, &T) + Send + Sync + UnwindSafe + RefUnwindSafe + 'static,
+    ) {
+        self.1.observers.on_remove.push(Box::new(f));
+    }
+
+    /// Unregisters all the `on_add` and `on_remove` callbacks.
+    pub fn clear_observers(&mut self) {
+        self.1.observers = Default::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Entity, EntityList};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn observer() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let mut el = EntityList::default();
+        let log2 = log.clone();
+        el.on_add(move |id, e: &Entity| log2.lock().unwrap().push(("add", id, e.name)));
+        let log2 = log.clone();
+        el.on_remove(move |id, e: &Entity| log2.lock().unwrap().push(("remove", id, e.name)));
+
+        let a = el.add(Entity { name: "a" });
+        let b = el.try_add(Entity { name: "b" }).unwrap();
+        el.remove(a);
+        assert_eq!(el.remove(a), None);
+        el.swap_remove_alive(b);
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                ("add", a, "a"),
+                ("add", b, "b"),
+                ("remove", a, "a"),
+                ("remove", b, "b")
+            ]
+        );
+
+        el.clear_observers();
+        el.add(Entity { name: "c" });
+        assert_eq!(log.lock().unwrap().len(), 4);
+    }
+}
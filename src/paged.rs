@@ -0,0 +1,338 @@
+use crate::{EntityEntry, EntityId, IndexWidth};
+use std::convert::TryInto;
+use std::ops::{Index, IndexMut};
+
+type Page<T, const PAGE_SIZE: usize> = Box<[EntityEntry<T>; PAGE_SIZE]>;
+
+/// An EntityList that allocates the slots in fixed-size pages, so that growing the list never
+/// moves existing entities. References obtained before `add` stay at the same address, e.g. for
+/// raw pointers passed to FFI callbacks, although the borrow checker still forbids holding them.
+///
+/// `PAGE_SIZE` must be a power of two, so that the index of an EntityId is
+/// `(page_index << log2(PAGE_SIZE)) | offset_in_page`. The EntityIds are not interchangeable with
+/// the ones of an [`EntityList`](crate::EntityList).
+pub struct PagedEntityList<T, const PAGE_SIZE: usize> {
+    pages: Vec<Page<T, PAGE_SIZE>>,
+    /// The number of slots in use, like the length of the backing Vec of an EntityList.
+    slot_count: usize,
+    /// The indices of the slots vacated by `remove`, reused last in first out.
+    free: Vec<usize>,
+}
+
+impl<T, const PAGE_SIZE: usize> Default for PagedEntityList<T, PAGE_SIZE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const PAGE_SIZE: usize> PagedEntityList<T, PAGE_SIZE> {
+    const LOG2_PAGE_SIZE: u32 = PAGE_SIZE.trailing_zeros();
+
+    /// Panics if `PAGE_SIZE` is not a power of two.
+    #[must_use = "creating an EntityList without using it is likely a bug"]
+    pub fn new() -> Self {
+        assert!(
+            PAGE_SIZE.is_power_of_two(),
+            "PagedEntityList: PAGE_SIZE must be a power of two"
+        );
+        Self {
+            pages: vec![],
+            slot_count: 0,
+            free: vec![],
+        }
+    }
+
+    fn new_page() -> Page<T, PAGE_SIZE> {
+        let slots: Box<[EntityEntry<T>]> = (0..PAGE_SIZE)
+            .map(|_| EntityEntry {
+                gen: 0,
+                entity: None,
+            })
+            .collect();
+        match slots.try_into() {
+            Ok(page) => page,
+            Err(_) => unreachable!(),
+        }
+    }
+
+    fn slot(&self, index: usize) -> Option<&EntityEntry<T>> {
+        if self.slot_count <= index {
+            return None;
+        }
+        Some(&self.pages[index >> Self::LOG2_PAGE_SIZE][index & (PAGE_SIZE - 1)])
+    }
+
+    fn slot_mut(&mut self, index: usize) -> Option<&mut EntityEntry<T>> {
+        if self.slot_count <= index {
+            return None;
+        }
+        Some(&mut self.pages[index >> Self::LOG2_PAGE_SIZE][index & (PAGE_SIZE - 1)])
+    }
+
+    fn slots(&self) -> impl Iterator<Item = &EntityEntry<T>> {
+        self.pages
+            .iter()
+            .flat_map(|page| page.iter())
+            .take(self.slot_count)
+    }
+
+    fn slots_mut(&mut self) -> impl Iterator<Item = &mut EntityEntry<T>> {
+        let slot_count = self.slot_count;
+        self.pages
+            .iter_mut()
+            .flat_map(|page| page.iter_mut())
+            .take(slot_count)
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots().filter(|entry| entry.entity.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots().all(|entry| entry.entity.is_none())
+    }
+
+    /// Returns the number of slots in use, including dead ones.
+    pub fn slot_count(&self) -> usize {
+        self.slot_count
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Adds an entity to the most recently vacated slot, or to a new slot in the last page, in
+    /// O(1). A new page is allocated if the last one is full, but no entity is moved.
+    ///
+    /// Like in an EntityList, slots whose generations are exhausted are not reused.
+    ///
+    /// Panics if the slots exceed the indices of `u32`.
+    pub fn add(&mut self, entity: T) -> EntityId {
+        while let Some(index) = self.free.pop() {
+            let entry = match self.slot_mut(index) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            if let Some(gen) = entry.reusable_gen() {
+                entry.entity = Some(entity);
+                entry.gen = gen;
+                return EntityId {
+                    id: u32::from_usize(index),
+                    gen,
+                };
+            }
+        }
+
+        assert!(
+            self.slot_count < u32::MAX_SLOTS,
+            "PagedEntityList: slot index overflow"
+        );
+        if self.slot_count == self.pages.len() * PAGE_SIZE {
+            self.pages.push(Self::new_page());
+        }
+        let index = self.slot_count;
+        self.slot_count += 1;
+        let entry = self.slot_mut(index).unwrap();
+        entry.entity = Some(entity);
+        EntityId {
+            id: u32::from_usize(index),
+            gen: entry.gen,
+        }
+    }
+
+    pub fn remove(&mut self, id: EntityId) -> Option<T> {
+        let index = id.id.to_usize();
+        let entry = self.slot_mut(index)?;
+        if entry.gen != id.gen {
+            return None;
+        }
+        let entity = entry.entity.take()?;
+        self.free.push(index);
+        Some(entity)
+    }
+
+    pub fn get(&self, id: EntityId) -> Option<&T> {
+        let entry = self.slot(id.id.to_usize())?;
+        if entry.gen == id.gen {
+            entry.entity.as_ref()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, id: EntityId) -> Option<&mut T> {
+        let entry = self.slot_mut(id.id.to_usize())?;
+        if entry.gen == id.gen {
+            entry.entity.as_mut()
+        } else {
+            None
+        }
+    }
+
+    /// Like [`EntityList::get_pair_mut`](crate::EntityList::get_pair_mut), returns the entity
+    /// only once if both EntityIds point to the same slot. The slots may be in the same page or
+    /// in different ones.
+    pub fn get_pair_mut(&mut self, a: EntityId, b: EntityId) -> (Option<&mut T>, Option<&mut T>) {
+        let (a_index, b_index) = (a.id.to_usize(), b.id.to_usize());
+        if a_index == b_index {
+            if self.get(a).is_some() {
+                return (self.get_mut(a), None);
+            }
+            return (None, self.get_mut(b));
+        }
+        if self.slot_count <= a_index {
+            return (None, self.get_mut(b));
+        }
+        if self.slot_count <= b_index {
+            return (self.get_mut(a), None);
+        }
+
+        let (a_page, b_page) = (
+            a_index >> Self::LOG2_PAGE_SIZE,
+            b_index >> Self::LOG2_PAGE_SIZE,
+        );
+        let (a_offset, b_offset) = (a_index & (PAGE_SIZE - 1), b_index & (PAGE_SIZE - 1));
+        let (a_entry, b_entry) = if a_page == b_page {
+            pair_mut(&mut self.pages[a_page][..], a_offset, b_offset)
+        } else {
+            let (a_page, b_page) = pair_mut(&mut self.pages, a_page, b_page);
+            (&mut a_page[a_offset], &mut b_page[b_offset])
+        };
+        (a_entry.get_mut(a), b_entry.get_mut(b))
+    }
+
+    /// Iterates alive entities with their EntityIds in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, &T)> {
+        self.slots().enumerate().filter_map(|(i, entry)| {
+            Some((
+                EntityId {
+                    id: u32::from_usize(i),
+                    gen: entry.gen,
+                },
+                entry.entity.as_ref()?,
+            ))
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (EntityId, &mut T)> {
+        self.slots_mut().enumerate().filter_map(|(i, entry)| {
+            Some((
+                EntityId {
+                    id: u32::from_usize(i),
+                    gen: entry.gen,
+                },
+                entry.entity.as_mut()?,
+            ))
+        })
+    }
+
+    /// Iterates the EntityIds of alive entities in slot order.
+    pub fn ids(&self) -> impl Iterator<Item = EntityId> + '_ {
+        self.iter().map(|(id, _)| id)
+    }
+}
+
+/// Borrows two distinct items of a slice mutably.
+fn pair_mut<E>(items: &mut [E], i: usize, j: usize) -> (&mut E, &mut E) {
+    if i < j {
+        let (left, right) = items.split_at_mut(j);
+        (&mut left[i], &mut right[0])
+    } else {
+        let (left, right) = items.split_at_mut(i);
+        (&mut right[0], &mut left[j])
+    }
+}
+
+impl<T, const PAGE_SIZE: usize> Index<EntityId> for PagedEntityList<T, PAGE_SIZE> {
+    type Output = T;
+
+    /// Panics if the EntityId is stale.
+    #[track_caller]
+    fn index(&self, id: EntityId) -> &T {
+        match self.get(id) {
+            Some(entity) => entity,
+            None => panic!("PagedEntityList: no entity with id {}", id),
+        }
+    }
+}
+
+impl<T, const PAGE_SIZE: usize> IndexMut<EntityId> for PagedEntityList<T, PAGE_SIZE> {
+    #[track_caller]
+    fn index_mut(&mut self, id: EntityId) -> &mut T {
+        match self.get_mut(id) {
+            Some(entity) => entity,
+            None => panic!("PagedEntityList: no entity with id {}", id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PagedEntityList;
+    use crate::Entity;
+
+    #[test]
+    fn paged() {
+        let mut el = PagedEntityList::<_, 2>::new();
+        let a = el.add(Entity { name: "a" });
+        let first: *const Entity = &el[a];
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        assert_eq!(el.page_count(), 2);
+        assert_eq!(&el[a] as *const Entity, first);
+        assert_eq!(el[c].name, "c");
+
+        assert_eq!(el.remove(b), Some(Entity { name: "b" }));
+        assert_eq!(el.get(b), None);
+        let d = el.add(Entity { name: "d" });
+        assert_eq!((d.id, d.gen), (1, 1));
+        el[d].name = "dd";
+        assert_eq!(
+            el.iter().map(|(_, e)| e.name).collect::<Vec<_>>(),
+            ["a", "dd", "c"]
+        );
+        assert_eq!(el.len(), 3);
+        assert_eq!(el.slot_count(), 3);
+        assert_eq!(el.ids().collect::<Vec<_>>(), [a, d, c]);
+
+        // The most recently vacated slot is reused, unless its generation is exhausted.
+        el.remove(a);
+        el.remove(c);
+        assert_eq!(el.add(Entity { name: "e" }).id, 2);
+        el.pages[0][0].gen = u32::MAX - 1;
+        let f = el.add(Entity { name: "f" });
+        assert_eq!((f.id, f.gen), (3, 0));
+        assert_eq!(el.slot_count(), 4);
+    }
+
+    #[test]
+    fn paged_get_pair_mut() {
+        let mut el = PagedEntityList::<_, 2>::new();
+        let ids: Vec<_> = (0..5).map(|i| el.add(i)).collect();
+
+        // In the same page and across pages, in both orders
+        for &(i, j) in &[(0, 1), (1, 0), (1, 4), (4, 1)] {
+            let (a, b) = el.get_pair_mut(ids[i], ids[j]);
+            let (a, b) = (a.unwrap(), b.unwrap());
+            std::mem::swap(a, b);
+            assert_eq!((el[ids[i]], el[ids[j]]), (j, i));
+            let (a, b) = el.get_pair_mut(ids[i], ids[j]);
+            std::mem::swap(a.unwrap(), b.unwrap());
+        }
+
+        assert_eq!(el.get_pair_mut(ids[2], ids[2]), (Some(&mut 2), None));
+        el.remove(ids[2]);
+        let c = el.add(5);
+        assert_eq!(el.get_pair_mut(ids[2], c), (None, Some(&mut 5)));
+        assert_eq!(el.get_pair_mut(ids[3], ids[2]), (Some(&mut 3), None));
+        let beyond = crate::EntityId { id: 8, gen: 0 };
+        assert_eq!(el.get_pair_mut(beyond, ids[0]), (None, Some(&mut 0)));
+        assert_eq!(el.get_pair_mut(ids[4], beyond), (Some(&mut 4), None));
+    }
+
+    #[test]
+    #[should_panic(expected = "PAGE_SIZE must be a power of two")]
+    fn paged_size() {
+        let _ = PagedEntityList::<Entity, 3>::new();
+    }
+}
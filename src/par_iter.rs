@@ -0,0 +1,104 @@
+use crate::{EntityId, EntityList};
+use rayon::prelude::*;
+
+impl<T: Sync> EntityList<T> {
+    /// Parallel version of iteration over alive entities. Dead slots are skipped in each worker.
+    pub fn par_iter(&self) -> impl ParallelIterator<Item = (EntityId, &T)> {
+        self.0.par_iter().enumerate().filter_map(|(i, entry)| {
+            Some((
+                EntityId {
+                    id: i as u32,
+                    gen: entry.gen,
+                },
+                entry.entity.as_ref()?,
+            ))
+        })
+    }
+}
+
+impl<T: Send> EntityList<T> {
+    pub fn par_iter_mut(&mut self) -> impl ParallelIterator<Item = (EntityId, &mut T)> {
+        self.0.par_iter_mut().enumerate().filter_map(|(i, entry)| {
+            Some((
+                EntityId {
+                    id: i as u32,
+                    gen: entry.gen,
+                },
+                entry.entity.as_mut()?,
+            ))
+        })
+    }
+}
+
+/// The number of slots evaluated by a worker at once in `par_retain`.
+const RETAIN_CHUNK_SIZE: usize = 4096;
+
+impl<T: Sync> EntityList<T> {
+    /// Keeps only the alive entities for which `f` returns true, evaluating `f` in parallel.
+    ///
+    /// The predicate is evaluated over chunks of the slots with shared access, and then the
+    /// rejected entities are removed sequentially, so it pays off when `f` is expensive. Like
+    /// `remove`, the list may be compacted at the end if a tombstone limit is set.
+    pub fn par_retain<F: Fn(EntityId, &T) -> bool + Send + Sync>(&mut self, f: F) {
+        let rejected: Vec<EntityId> = self
+            .0
+            .par_chunks(RETAIN_CHUNK_SIZE)
+            .enumerate()
+            .flat_map_iter(|(chunk, entries)| {
+                let f = &f;
+                entries.iter().enumerate().filter_map(move |(i, entry)| {
+                    let id = EntityId {
+                        id: (chunk * RETAIN_CHUNK_SIZE + i) as u32,
+                        gen: entry.gen,
+                    };
+                    if f(id, entry.entity.as_ref()?) {
+                        None
+                    } else {
+                        Some(id)
+                    }
+                })
+            })
+            .collect();
+        for id in rejected {
+            let _ = self.take(id);
+        }
+        self.compact_over_tombstone_limit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::EntityList;
+    use rayon::prelude::*;
+
+    #[test]
+    fn par_iter() {
+        let (mut el, ids) = EntityList::from_iter_with_ids(0..1000);
+        for id in ids.iter().step_by(3) {
+            el.remove(*id);
+        }
+
+        el.par_iter_mut().for_each(|(_, v)| *v *= 2);
+        let mut items: Vec<_> = el.par_iter().map(|(id, v)| (id, *v)).collect();
+        items.sort_by_key(|(id, _)| id.id);
+
+        let expected: Vec<_> = ids
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i % 3 != 0)
+            .map(|(i, id)| (*id, i as i32 * 2))
+            .collect();
+        assert_eq!(items, expected);
+    }
+
+    #[test]
+    fn par_retain() {
+        let (mut el, ids) = EntityList::from_iter_with_ids(0..10_000);
+        el.remove(ids[1]);
+        el.par_retain(|_, v| v % 2 == 1);
+        assert_eq!(el.len(), 4999);
+        assert!(el.iter().all(|(_, v)| v % 2 == 1));
+        assert_eq!(el.get(ids[9999]), Some(&9999));
+        assert_eq!(el.get(ids[4096]), None);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{EntityId, EntityList};
+use std::collections::HashMap;
+
+/// An entity type that stores EntityIds of other entities, which need to be updated when the
+/// entities are moved by e.g. [`EntityList::compact`].
+pub trait HasEntityIds {
+    /// Replaces each stored EntityId found in `map` with the new one.
+    fn remap_ids(&mut self, map: &HashMap<EntityId, EntityId>);
+}
+
+/// A marker for entity types without EntityIds, which get a no-op [`HasEntityIds`].
+pub trait NoEntityIds {}
+
+impl<T: NoEntityIds> HasEntityIds for T {
+    fn remap_ids(&mut self, _map: &HashMap<EntityId, EntityId>) {}
+}
+
+impl<T> EntityList<T> {
+    /// Calls [`HasEntityIds::remap_ids`] on all the alive entities, e.g. with the (old, new)
+    /// EntityIds returned by [`EntityList::compact`] collected into a HashMap.
+    pub fn remap_entity_ids(&mut self, remap: &HashMap<EntityId, EntityId>)
+    where
+        T: HasEntityIds,
+    {
+        for (_, entity) in self.iter_mut() {
+            entity.remap_ids(remap);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HasEntityIds, NoEntityIds};
+    use crate::{EntityId, EntityList};
+    use std::collections::HashMap;
+
+    struct Node {
+        parent: Option<EntityId>,
+    }
+
+    impl HasEntityIds for Node {
+        fn remap_ids(&mut self, map: &HashMap<EntityId, EntityId>) {
+            if let Some(new) = self.parent.and_then(|parent| map.get(&parent)) {
+                self.parent = Some(*new);
+            }
+        }
+    }
+
+    struct Leaf;
+
+    impl NoEntityIds for Leaf {}
+
+    #[test]
+    fn remap_entity_ids() {
+        let mut el = EntityList::default();
+        let a = el.add(Node { parent: None });
+        let b = el.add(Node { parent: Some(a) });
+        let c = el.add(Node { parent: Some(b) });
+        el.remove(a);
+        el[b].parent = None;
+
+        let remap: HashMap<_, _> = el.compact().into_iter().collect();
+        el.remap_entity_ids(&remap);
+        let new_b = remap[&b];
+        let new_c = remap[&c];
+        assert_eq!(el[new_c].parent, Some(new_b));
+
+        let mut leaves = EntityList::default();
+        leaves.add(Leaf);
+        leaves.remap_entity_ids(&remap);
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{EntityId, EntityList};
+
+/// A companion storage to attach another component to the entities of an EntityList.
+///
+/// Each slot remembers the generation of the EntityId it was inserted with, so that a stale
+/// EntityId whose slot has been reused does not see the component of the new entity.
+pub struct SecondaryMap<T>(Vec<Option<(u32, T)>>);
+
+impl<T> Default for SecondaryMap<T> {
+    fn default() -> Self {
+        Self(vec![])
+    }
+}
+
+impl<T> SecondaryMap<T> {
+    /// Inserts a component for `id`, replacing the one of an older generation if any.
+    pub fn insert(&mut self, id: EntityId, value: T) {
+        let idx = id.id as usize;
+        if self.0.len() <= idx {
+            self.0.resize_with(idx + 1, || None);
+        }
+        self.0[idx] = Some((id.gen, value));
+    }
+
+    pub fn get(&self, id: EntityId) -> Option<&T> {
+        match self.0.get(id.id as usize)? {
+            Some((gen, value)) if *gen == id.gen => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, id: EntityId) -> Option<&mut T> {
+        match self.0.get_mut(id.id as usize)? {
+            Some((gen, value)) if *gen == id.gen => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn remove(&mut self, id: EntityId) -> Option<T> {
+        let slot = self.0.get_mut(id.id as usize)?;
+        if slot.as_ref()?.0 != id.gen {
+            return None;
+        }
+        slot.take().map(|(_, value)| value)
+    }
+
+    /// Removes all the components whose entities are no longer alive in `list`.
+    pub fn clear_stale<U>(&mut self, list: &EntityList<U>) {
+        for (i, slot) in self.0.iter_mut().enumerate() {
+            if let Some((gen, _)) = slot {
+                let id = EntityId {
+                    id: i as u32,
+                    gen: *gen,
+                };
+                if list.get(id).is_none() {
+                    *slot = None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SecondaryMap;
+    use crate::{Entity, EntityList};
+
+    #[test]
+    fn secondary() {
+        let mut el = EntityList::default();
+        let mut health = SecondaryMap::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        health.insert(a, 10);
+        health.insert(c, 30);
+        assert_eq!(health.get(a), Some(&10));
+        assert_eq!(health.get(b), None);
+
+        *health.get_mut(c).unwrap() += 1;
+        assert_eq!(health.remove(c), Some(31));
+        assert_eq!(health.remove(c), None);
+
+        el.remove(a);
+        let d = el.add(Entity { name: "d" });
+        assert_eq!(health.get(d), None);
+        assert_eq!(health.get(a), Some(&10));
+
+        health.insert(b, 20);
+        health.clear_stale(&el);
+        assert_eq!(health.get(a), None);
+        assert_eq!(health.get(b), Some(&20));
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{EntityEntry, EntityId, EntityList};
+
+/// A point-in-time copy of the slots of an [`EntityList`], for undo/redo or replays.
+///
+/// Since the generations are copied as well, EntityIds issued before the snapshot resolve to the
+/// same entities after [`EntityList::restore`].
+#[derive(Clone)]
+pub struct EntityListSnapshot<T: Clone>(Vec<EntityEntry<T>>, usize);
+
+impl<T: Clone> EntityListSnapshot<T> {
+    /// Returns the number of alive entities in the snapshot.
+    pub fn len(&self) -> usize {
+        self.1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.1 == 0
+    }
+}
+
+/// Lifecycle changes between an [`EntityListSnapshot`] and the current state, returned by
+/// [`EntityList::diff`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EntityListDiff {
+    /// EntityIds of the entities that are alive only in the current state.
+    pub added: Vec<EntityId>,
+    /// EntityIds of the entities that were alive only in the snapshot.
+    pub removed: Vec<EntityId>,
+    /// EntityIds of the entities alive in both but with different values.
+    pub modified: Vec<EntityId>,
+}
+
+impl<T> EntityList<T> {
+    pub fn snapshot(&self) -> EntityListSnapshot<T>
+    where
+        T: Clone,
+    {
+        EntityListSnapshot(self.0.clone(), self.len())
+    }
+
+    /// Replaces the slots with the ones in the snapshot. Settings such as
+    /// [`EntityList::with_max_entities`] are kept, and so are the generations of the slots
+    /// beyond the snapshot, for the slots pushed later.
+    pub fn restore(&mut self, snapshot: EntityListSnapshot<T>)
+    where
+        T: Clone,
+    {
+        if snapshot.0.len() < self.0.len() {
+            self.drop_slots_from(snapshot.0.len());
+        }
+        self.0 = snapshot.0;
+        self.rebuild_free_list();
+        self.1.version += 1;
+        #[cfg(feature = "dirty-tracking")]
+        self.clear_all_dirty();
+    }
+
+    /// Computes the changes since the snapshot was taken, slot by slot.
+    ///
+    /// A slot whose generation differs has been cycled, so the old entity counts as removed and
+    /// the new one, if any, as added. Note that a slot that was removed and added back without
+    /// going through [`EntityList::add`] keeps its generation and shows up as modified.
+    pub fn diff(&self, before: &EntityListSnapshot<T>) -> EntityListDiff
+    where
+        T: Clone + PartialEq,
+    {
+        let mut diff = EntityListDiff::default();
+        let slots = self.0.len().max(before.0.len());
+        for i in 0..slots {
+            let id_of = |entry: &EntityEntry<T>| EntityId {
+                id: i as u32,
+                gen: entry.gen,
+            };
+            let old = before.0.get(i).filter(|entry| entry.entity.is_some());
+            let new = self.0.get(i).filter(|entry| entry.entity.is_some());
+            match (old, new) {
+                (Some(old), Some(new)) if old.gen == new.gen => {
+                    if old.entity != new.entity {
+                        diff.modified.push(id_of(new));
+                    }
+                }
+                (old, new) => {
+                    if let Some(old) = old {
+                        diff.removed.push(id_of(old));
+                    }
+                    if let Some(new) = new {
+                        diff.added.push(id_of(new));
+                    }
+                }
+            }
+        }
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Entity, EntityList, EntityListDiff};
+
+    #[test]
+    fn snapshot() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        el.remove(b);
+        let before: Vec<_> = el.iter().map(|(id, e)| (id, e.clone())).collect();
+        let snapshot = el.snapshot();
+        assert_eq!(snapshot.len(), 2);
+
+        el[a].name = "aa";
+        el.remove(c);
+        let d = el.add(Entity { name: "d" });
+        el.add(Entity { name: "e" });
+
+        el.restore(snapshot);
+        let after: Vec<_> = el.iter().map(|(id, e)| (id, e.clone())).collect();
+        assert_eq!(after, before);
+        assert_eq!(el.slot_count(), 3);
+        assert_eq!(el.get(d), None);
+
+        // Replaying the changes issues the same EntityIds
+        el.remove(c);
+        assert_eq!(el.add(Entity { name: "f" }), d);
+    }
+
+    #[test]
+    fn diff() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let c = el.add(Entity { name: "c" });
+        el.remove(c);
+        let snapshot = el.snapshot();
+        assert_eq!(el.diff(&snapshot), EntityListDiff::default());
+
+        el[a].name = "aa";
+        el.remove(b);
+        let d = el.add(Entity { name: "d" });
+        let e = el.add(Entity { name: "e" });
+        let f = el.add(Entity { name: "f" });
+        assert_eq!(
+            el.diff(&snapshot),
+            EntityListDiff {
+                added: vec![d, e, f],
+                removed: vec![b],
+                modified: vec![a],
+            }
+        );
+
+        el.0.truncate(1);
+        let diff = el.diff(&snapshot);
+        assert_eq!((diff.added, diff.removed), (vec![], vec![b]));
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{EntityId, EntityList};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// A thread-safe wrapper of [`EntityList`] for the single-writer/many-reader pattern.
+///
+/// A typical game loop lets many worker threads read the entities in parallel, and then applies
+/// the changes in a single thread between the frames.
+///
+/// ```
+/// use entity_list::{sync::SyncEntityList, EntityList};
+///
+/// let list = SyncEntityList::new(EntityList::default());
+/// let ids: Vec<_> = (0..4).map(|i| list.add(i)).collect();
+///
+/// for _frame in 0..2 {
+///     // Read phase: every thread holds a read lock at the same time.
+///     let sum: i32 = std::thread::scope(|s| {
+///         let handles: Vec<_> = ids
+///             .iter()
+///             .map(|&id| {
+///                 let list = &list;
+///                 s.spawn(move || *list.read().get(id).unwrap())
+///             })
+///             .collect();
+///         handles.into_iter().map(|h| h.join().unwrap()).sum()
+///     });
+///
+///     // Write phase: a single thread applies the result.
+///     let mut writer = list.write();
+///     for id in &ids {
+///         *writer.get_mut(*id).unwrap() += sum;
+///     }
+/// }
+/// assert_eq!(list.get(ids[0]), Some(6 + 30));
+/// ```
+///
+/// It is `Send` if `T: Send` and `Sync` if `T: Send + Sync`, as required by [`RwLock`].
+///
+/// All the methods panic if the lock is poisoned, i.e. another thread panicked while holding it.
+pub struct SyncEntityList<T>(RwLock<EntityList<T>>);
+
+impl<T> Default for SyncEntityList<T> {
+    fn default() -> Self {
+        Self::new(EntityList::default())
+    }
+}
+
+impl<T> SyncEntityList<T> {
+    pub fn new(list: EntityList<T>) -> Self {
+        Self(RwLock::new(list))
+    }
+
+    pub fn into_inner(self) -> EntityList<T> {
+        self.0.into_inner().unwrap()
+    }
+
+    pub fn read(&self) -> RwLockReadGuard<'_, EntityList<T>> {
+        self.0.read().unwrap()
+    }
+
+    pub fn write(&self) -> RwLockWriteGuard<'_, EntityList<T>> {
+        self.0.write().unwrap()
+    }
+
+    /// Returns a copy of the entity, since a reference cannot outlive the lock.
+    pub fn get(&self, id: EntityId) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.read().get(id).cloned()
+    }
+
+    pub fn add(&self, entity: T) -> EntityId {
+        self.write().add(entity)
+    }
+
+    pub fn remove(&self, id: EntityId) -> Option<T> {
+        self.write().remove(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SyncEntityList;
+
+    #[test]
+    fn sync_list() {
+        let list = SyncEntityList::default();
+        let a = list.add("a");
+        let b = list.add("b");
+
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    assert_eq!(list.get(a), Some("a"));
+                    assert_eq!(list.read().len(), 2);
+                });
+            }
+        });
+
+        assert_eq!(list.remove(b), Some("b"));
+        assert_eq!(list.get(b), None);
+        assert_eq!(list.into_inner().len(), 1);
+    }
+}
@@ -0,0 +1,132 @@
+//! Integrity checks of the slots and their bookkeeping by [`EntityList::validate`].
+
+#[cfg(doc)]
+use crate::EntityList;
+use crate::{EntityListImpl, GenWidth, IndexWidth};
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ValidationKind {
+    /// The alive counter differs from the number of alive slots. The slot index is the first
+    /// alive one over the counter, or the number of slots if the counter is higher.
+    AliveCountMismatch,
+    /// An alive entity has the generation `G::MAX`, which is never issued since it would make
+    /// the slot wrap around when reused.
+    ExhaustedGeneration,
+    /// The free list has an index beyond the slots.
+    FreeSlotOutOfBounds,
+    /// The free list has the index of an alive slot.
+    FreeSlotAlive,
+    /// The free list has the same index more than once.
+    DuplicateFreeSlot,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ValidationError {
+    pub kind: ValidationKind,
+    pub slot_index: usize,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} at slot {}", self.kind, self.slot_index)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl<T, I: IndexWidth, G: GenWidth> EntityListImpl<T, I, G> {
+    /// Checks the internal consistency and returns the first violation found.
+    ///
+    /// It scans all the slots, so it is meant for tests and debug assertions.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let error = |kind, slot_index| Err(ValidationError { kind, slot_index });
+        let mut alive = 0;
+        for (i, entry) in self.0.iter().enumerate() {
+            if entry.entity.is_none() {
+                continue;
+            }
+            alive += 1;
+            if self.1.alive < alive {
+                return error(ValidationKind::AliveCountMismatch, i);
+            }
+            if entry.gen == G::MAX {
+                return error(ValidationKind::ExhaustedGeneration, i);
+            }
+        }
+        if alive != self.1.alive {
+            return error(ValidationKind::AliveCountMismatch, self.0.len());
+        }
+
+        let mut listed = vec![false; self.0.len()];
+        for index in self.1.free.iter().map(|index| index.to_usize()) {
+            match self.0.get(index) {
+                None => return error(ValidationKind::FreeSlotOutOfBounds, index),
+                Some(entry) if entry.entity.is_some() => {
+                    return error(ValidationKind::FreeSlotAlive, index)
+                }
+                Some(_) if listed[index] => return error(ValidationKind::DuplicateFreeSlot, index),
+                Some(_) => listed[index] = true,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ValidationError, ValidationKind};
+    use crate::{Entity, EntityList};
+
+    #[test]
+    fn validate() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        assert_eq!(el.validate(), Ok(()));
+
+        el.remove(b);
+        el.0[b.id as usize].gen = u32::MAX;
+        assert_eq!(el.validate(), Ok(()));
+
+        el.0[a.id as usize].gen = u32::MAX;
+        assert_eq!(
+            el.validate(),
+            Err(ValidationError {
+                kind: ValidationKind::ExhaustedGeneration,
+                slot_index: 0
+            })
+        );
+    }
+
+    #[test]
+    fn validate_bookkeeping() {
+        let check = |el: &EntityList<Entity>, kind, slot_index| {
+            assert_eq!(el.validate(), Err(ValidationError { kind, slot_index }));
+        };
+        let mut el = EntityList::default();
+        el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        el.add(Entity { name: "c" });
+        el.remove(b);
+        assert_eq!(el.validate(), Ok(()));
+
+        let mut broken = el.clone();
+        broken.1.alive = 1;
+        check(&broken, ValidationKind::AliveCountMismatch, 2);
+        broken.1.alive = 3;
+        check(&broken, ValidationKind::AliveCountMismatch, 3);
+
+        let mut broken = el.clone();
+        broken.1.free.push(3);
+        check(&broken, ValidationKind::FreeSlotOutOfBounds, 3);
+
+        let mut broken = el.clone();
+        broken.1.free.push(0);
+        check(&broken, ValidationKind::FreeSlotAlive, 0);
+
+        let mut broken = el.clone();
+        broken.1.free.push(1);
+        check(&broken, ValidationKind::DuplicateFreeSlot, 1);
+    }
+}
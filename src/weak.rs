@@ -0,0 +1,74 @@
+use crate::{EntityId, EntityList};
+
+/// An EntityId that also remembers the number of slots in the list when it was issued.
+///
+/// A plain EntityId already behaves like a weak reference, since a stale one resolves to None.
+/// This additionally rejects EntityIds beyond the end while the slots are fewer than the number
+/// at issuance, e.g. after [`EntityList::compact`] or [`EntityList::split_off_at`].
+///
+/// The detection fails once the list regrows past that number, so it does not protect against
+/// aliasing by itself. That is prevented by the list instead, which starts the slots pushed after
+/// a truncation above the generations of the dropped ones, and never reuses a slot whose
+/// generation would wrap around. So prefer EntityId, which is 4 bytes smaller.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct WeakEntityId {
+    id: EntityId,
+    slot_count_at_issue: u32,
+}
+
+impl<T> EntityList<T> {
+    pub fn make_weak(&self, id: EntityId) -> WeakEntityId {
+        WeakEntityId {
+            id,
+            slot_count_at_issue: self.0.len() as u32,
+        }
+    }
+
+    /// Returns the EntityId if the entity is still alive and the list has not shrunk since the
+    /// WeakEntityId was issued.
+    pub fn upgrade(&self, w: &WeakEntityId) -> Option<EntityId> {
+        if (self.0.len() as u32) < w.slot_count_at_issue {
+            return None;
+        }
+        self.get(w.id)?;
+        Some(w.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Entity, EntityList};
+
+    #[test]
+    fn weak() {
+        let mut el = EntityList::default();
+        let a = el.add(Entity { name: "a" });
+        let b = el.add(Entity { name: "b" });
+        let weak_a = el.make_weak(a);
+        let weak_b = el.make_weak(b);
+        assert_eq!(el.upgrade(&weak_a), Some(a));
+
+        el.remove(a);
+        el.add(Entity { name: "c" });
+        assert_eq!(el.upgrade(&weak_a), None);
+
+        assert_eq!(el.upgrade(&weak_b), Some(b));
+        el.0.truncate(1);
+        assert_eq!(el.upgrade(&weak_b), None);
+    }
+
+    #[test]
+    fn weak_after_compact_and_regrowth() {
+        let mut el = EntityList::default();
+        let ids: Vec<_> = (0..4).map(|i| el.add(i)).collect();
+        let weak = el.make_weak(ids[3]);
+        el.remove(ids[0]);
+        el.remove(ids[3]);
+        el.compact();
+        el.add(10);
+        el.add(11);
+        assert_eq!(el.slot_count(), 4);
+        assert_eq!(el.get(ids[3]), None);
+        assert_eq!(el.upgrade(&weak), None);
+    }
+}
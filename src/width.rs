@@ -0,0 +1,83 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::Hash;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// The integer type to store generations of slots, implemented for `u8`, `u16` and `u32`.
+///
+/// A narrower generation makes [`EntityEntry`](crate::EntityEntry) smaller, but the slots are
+/// exhausted sooner when they are reused. `MAX` is never issued, so a slot whose generation
+/// cannot be bumped below it is retired and `add` skips it.
+pub trait GenWidth:
+    sealed::Sealed + Copy + Eq + Ord + Hash + Default + fmt::Debug + fmt::Display + Into<u32>
+{
+    const MAX: Self;
+
+    /// Returns the next generation, which overflows like `+ 1` does.
+    fn next_gen(self) -> Self;
+
+    /// Returns the next generation unless it would reach `MAX`, i.e. the slot is exhausted.
+    fn checked_next_gen(self) -> Option<Self>;
+}
+
+macro_rules! impl_gen_width {
+    ($($t:ty),*) => {
+        $(
+            impl sealed::Sealed for $t {}
+
+            impl GenWidth for $t {
+                const MAX: Self = <$t>::MAX;
+
+                fn next_gen(self) -> Self {
+                    self + 1
+                }
+
+                fn checked_next_gen(self) -> Option<Self> {
+                    self.checked_add(1).filter(|gen| *gen != Self::MAX)
+                }
+            }
+        )*
+    };
+}
+
+impl_gen_width!(u8, u16, u32);
+
+/// The integer type to store slot indices, implemented for `u16` and `u32`.
+///
+/// It also limits the number of entities, so that `try_add` fails and `add` panics once all the
+/// indices are used.
+pub trait IndexWidth:
+    sealed::Sealed + Copy + Eq + Ord + Hash + Default + fmt::Debug + fmt::Display
+{
+    /// The number of slots that can be indexed.
+    const MAX_SLOTS: usize;
+
+    fn to_usize(self) -> usize;
+
+    /// Panics if the index does not fit, which `add` prevents by checking `MAX_SLOTS` before
+    /// pushing a slot.
+    fn from_usize(index: usize) -> Self;
+}
+
+macro_rules! impl_index_width {
+    ($($t:ty),*) => {
+        $(
+            impl IndexWidth for $t {
+                const MAX_SLOTS: usize = (<$t>::MAX as usize).saturating_add(1);
+
+                fn to_usize(self) -> usize {
+                    self as usize
+                }
+
+                fn from_usize(index: usize) -> Self {
+                    <$t>::try_from(index).expect("EntityList: slot index overflow")
+                }
+            }
+        )*
+    };
+}
+
+impl_index_width!(u16, u32);